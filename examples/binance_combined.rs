@@ -0,0 +1,51 @@
+use orderbook::binance::api::{UM, combined_stream_url, depth_stream};
+use orderbook::binance::combined::{CombinedDecoder, Router, StreamEvent, spawn_router};
+use orderbook::binance::decode::DepthDecoder;
+use orderbook::feed::Event;
+use orderbook::ws::{Backoff, connect_reconnecting};
+use std::time::Duration;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let _ = tokio_rustls::rustls::crypto::ring::default_provider().install_default();
+    let symbols = ["BTCUSDT", "ETHUSDT", "SOLUSDT"];
+
+    // One connection for every book
+    let router = Router::new();
+    let mut books = vec![];
+    for symbol in symbols {
+        let book = orderbook::binance::Book::new_um(symbol, 1000, Duration::from_millis(500));
+        router.insert(depth_stream(symbol), book.writer());
+        books.push((symbol, book));
+    }
+
+    let url = combined_stream_url(UM.ws_url(), &router.streams());
+    let ws = connect_reconnecting(&url, vec![], Backoff::default()).await?;
+    let mut events = spawn_router(ws, CombinedDecoder::new(DepthDecoder), router);
+
+    tokio::spawn(async move {
+        while let Some(StreamEvent { stream, event }) = events.recv().await {
+            if let Event::Error(e) = event {
+                eprintln!("Feed error on {:?}: {}", stream, e);
+            }
+        }
+    });
+
+    let tasks: Vec<_> = books
+        .into_iter()
+        .map(|(symbol, mut book)| {
+            tokio::spawn(async move {
+                while let Some(snapshot) = book.recv().await {
+                    let mid: f64 = snapshot.mid().into();
+                    println!("{} mid price: {:.2}", symbol, mid);
+                }
+            })
+        })
+        .collect();
+
+    for task in tasks {
+        task.await?;
+    }
+
+    Ok(())
+}
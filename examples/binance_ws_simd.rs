@@ -1,39 +1,35 @@
-use orderbook::binance::types::DepthUpdate;
+use orderbook::binance::types::{DepthUpdate, DepthUpdateSeq};
+use orderbook::feed::{Decoder, Event, spawn_feed};
 use orderbook::ws::connect;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+/// Parses depth updates in place with simd-json
+struct SimdDepthDecoder;
+
+impl Decoder for SimdDepthDecoder {
+    type Seq = DepthUpdateSeq;
+
+    fn decode(&mut self, frame: &mut [u8], events: &mut Vec<Event<DepthUpdateSeq>>) {
+        match simd_json::from_slice::<DepthUpdate>(frame) {
+            Ok(depth_update) => events.push(Event::Book(depth_update.into())),
+            Err(e) => events.push(Event::decode_error(e)),
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let _ = tokio_rustls::rustls::crypto::ring::default_provider().install_default();
     let url = "wss://fstream.binance.com/ws/btcusdt@depth";
-    let mut ws = connect(url).await?;
+    let ws = connect(url).await?;
 
     let mut book = orderbook::binance::Book::new_um("BTCUSDT", 1000, Duration::from_millis(0));
+    let mut events = spawn_feed(ws, SimdDepthDecoder, book.writer(), vec![]);
 
-    let writer = book.writer();
     tokio::spawn(async move {
-        loop {
-            let Some(res) = ws.rx.recv().await else { break };
-
-            let mut json = match res {
-                Ok(msg) => msg,
-                Err(e) => {
-                    eprintln!("Connection error: {}", e);
-                    break;
-                }
-            };
-
-            match simd_json::from_slice::<DepthUpdate>(&mut json) {
-                Ok(depth_update) => {
-                    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
-                    let latency = now - depth_update.seq.event_time_ms;
-                    println!("Received depth update latency: {}ms", latency);
-
-                    writer.update(depth_update.into()).await;
-                }
-                Err(e) => {
-                    eprintln!("Error parsing message: {:?}", e);
-                }
+        while let Some(event) = events.recv().await {
+            if let Event::Error(e) = event {
+                eprintln!("Feed error: {}", e);
             }
         }
     });
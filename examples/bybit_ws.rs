@@ -0,0 +1,35 @@
+use orderbook::bybit::api::{Category, orderbook_topic, subscribe, unsubscribe};
+use orderbook::bybit::decode::OrderbookDecoder;
+use orderbook::feed::{Event, spawn_feed};
+use orderbook::ws::{Backoff, connect_reconnecting};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let _ = tokio_rustls::rustls::crypto::ring::default_provider().install_default();
+    let topic = orderbook_topic(50, "BTCUSDT");
+    let ws = connect_reconnecting(Category::Linear.ws_url(), vec![subscribe(&[&topic]).into()], Backoff::default()).await?;
+
+    let mut book = orderbook::bybit::Book::new_bybit("BTCUSDT", 50, Duration::from_millis(0));
+
+    // Book lost sync, resubscribe to get a fresh snapshot
+    let resubscribe = vec![unsubscribe(&[&topic]).into(), subscribe(&[&topic]).into()];
+    let mut events = spawn_feed(ws, OrderbookDecoder, book.writer(), resubscribe);
+
+    tokio::spawn(async move {
+        while let Some(event) = events.recv().await {
+            if let Event::Error(e) = event {
+                eprintln!("Feed error: {}", e);
+            }
+        }
+    });
+
+    while let Some(snapshot) = book.recv().await {
+        let mid: f64 = snapshot.mid().into();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+        let latency = now - snapshot.ts_ms;
+        println!("Received snapshot mid price: {:.2}, latency: {}ms", mid, latency);
+    }
+
+    Ok(())
+}
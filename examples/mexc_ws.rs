@@ -0,0 +1,60 @@
+use orderbook::mexc::api::{Mexc, aggre_depth_channel, ping, subscribe};
+use orderbook::mexc::proto::decode;
+use orderbook::ws::connect;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let _ = tokio_rustls::rustls::crypto::ring::default_provider().install_default();
+    let mut ws = connect(Mexc.ws_url()).await?;
+    ws.tx.send(subscribe(&[aggre_depth_channel("BTCUSDT", "100ms")]).into()).await?;
+
+    let mut book = orderbook::mexc::Book::new_mexc("BTCUSDT", 1000, Duration::from_millis(0));
+
+    let writer = book.writer();
+    tokio::spawn(async move {
+        let mut keepalive = tokio::time::interval(Duration::from_secs(20));
+
+        loop {
+            let res = tokio::select! {
+                res = ws.rx.recv() => match res {
+                    Some(res) => res,
+                    None => break,
+                },
+                _ = keepalive.tick() => {
+                    let _ = ws.tx.send(ping().into()).await;
+                    continue;
+                }
+            };
+
+            let frame = match res {
+                Ok(msg) => msg,
+                Err(e) => {
+                    eprintln!("Connection error: {}", e);
+                    break;
+                }
+            };
+
+            // Acks and pongs are JSON text
+            if frame.first() == Some(&b'{') {
+                println!("Control message: {}", String::from_utf8_lossy(&frame));
+                continue;
+            }
+
+            match decode(&frame).map(|msg| msg.into_order()) {
+                Ok(Some(order)) => writer.update(order).await,
+                Ok(None) => {}
+                Err(e) => eprintln!("Error decoding message: {:?}", e),
+            }
+        }
+    });
+
+    while let Some(snapshot) = book.recv().await {
+        let mid: f64 = snapshot.mid().into();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+        let latency = now.saturating_sub(snapshot.ts_ms);
+        println!("Received snapshot mid price: {:.2}, latency: {}ms", mid, latency);
+    }
+
+    Ok(())
+}
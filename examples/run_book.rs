@@ -0,0 +1,28 @@
+use orderbook::binance::api::UM;
+use orderbook::feed::Event;
+use orderbook::venue::{RunOptions, run_book};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let _ = tokio_rustls::rustls::crypto::ring::default_provider().install_default();
+
+    let (mut book, mut events) = run_book::<UM>("BTCUSDT", RunOptions::default()).await?;
+
+    tokio::spawn(async move {
+        while let Some(event) = events.recv().await {
+            if let Event::Error(e) = event {
+                eprintln!("Feed error: {}", e);
+            }
+        }
+    });
+
+    while let Some(snapshot) = book.recv().await {
+        let mid: f64 = snapshot.mid().into();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+        let latency = now.saturating_sub(snapshot.ts_ms);
+        println!("Received snapshot mid price: {:.2}, latency: {}ms", mid, latency);
+    }
+
+    Ok(())
+}
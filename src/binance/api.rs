@@ -1,4 +1,14 @@
-use super::types::DepthSnapshot;
+use super::types::{DepthSnapshot, ExchangeInfo, OptionsExchangeInfo};
+use crate::l2_book::tokio::SnapshotLimiter;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Budget of one API shared by every depth request of the process, as Binance limits request
+/// weight per IP and minute. Half of `weight_per_minute` is left to other requests.
+/// A depth request weighs 20 with 1000 levels and 250 with the 5000 levels of spot.
+fn depth_limiter(limiter: &'static OnceLock<SnapshotLimiter>, weight_per_minute: u32) -> &'static SnapshotLimiter {
+    limiter.get_or_init(|| SnapshotLimiter::new(weight_per_minute / 2, Duration::from_secs(60)))
+}
 
 pub trait Rest {
     type Error: std::fmt::Debug + Send;
@@ -10,6 +20,7 @@ pub trait Rest {
 }
 
 /// Binance USD-Margin API
+#[derive(Debug, Clone, Copy, Default)]
 pub struct UM;
 
 impl UM {
@@ -26,6 +37,9 @@ impl Rest for UM {
     type Error = reqwest::Error;
 
     async fn get_orderbook(&self, symbol: &str) -> Result<DepthSnapshot, Self::Error> {
+        static LIMITER: OnceLock<SnapshotLimiter> = OnceLock::new();
+        depth_limiter(&LIMITER, 2400).acquire(20).await;
+
         let client = reqwest::Client::new();
         let url = format!("{}/fapi/v1/depth", self.rest_url());
 
@@ -39,3 +53,176 @@ impl Rest for UM {
         Ok(data)
     }
 }
+
+/// Binance Spot API
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Spot;
+
+impl Spot {
+    pub fn rest_url(&self) -> &str {
+        "https://api.binance.com"
+    }
+
+    pub fn ws_url(&self) -> &str {
+        "wss://stream.binance.com:9443"
+    }
+}
+
+impl Rest for Spot {
+    type Error = reqwest::Error;
+
+    async fn get_orderbook(&self, symbol: &str) -> Result<DepthSnapshot, Self::Error> {
+        static LIMITER: OnceLock<SnapshotLimiter> = OnceLock::new();
+        depth_limiter(&LIMITER, 6000).acquire(250).await;
+
+        let client = reqwest::Client::new();
+        let url = format!("{}/api/v3/depth", self.rest_url());
+
+        let resp = client
+            .get(&url)
+            .query(&[("symbol", symbol), ("limit", "5000")])
+            .send()
+            .await?;
+
+        let data = resp.json::<DepthSnapshot>().await?;
+        Ok(data)
+    }
+}
+
+/// Binance COIN-Margin API, books are quoted in contracts
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CM;
+
+impl CM {
+    pub fn rest_url(&self) -> &str {
+        "https://dapi.binance.com"
+    }
+
+    pub fn ws_url(&self) -> &str {
+        "wss://dstream.binance.com"
+    }
+
+    /// USD value of one contract of `symbol`, e.g. 100 for `BTCUSD_PERP`
+    pub async fn contract_size(&self, symbol: &str) -> Result<Option<f64>, reqwest::Error> {
+        let client = reqwest::Client::new();
+        let url = format!("{}/dapi/v1/exchangeInfo", self.rest_url());
+
+        let resp = client.get(&url).send().await?;
+        let data = resp.json::<ExchangeInfo>().await?;
+
+        Ok(data.symbols.into_iter().find(|s| s.symbol == symbol).map(|s| s.contract_size))
+    }
+}
+
+impl Rest for CM {
+    type Error = reqwest::Error;
+
+    async fn get_orderbook(&self, symbol: &str) -> Result<DepthSnapshot, Self::Error> {
+        static LIMITER: OnceLock<SnapshotLimiter> = OnceLock::new();
+        depth_limiter(&LIMITER, 2400).acquire(20).await;
+
+        let client = reqwest::Client::new();
+        let url = format!("{}/dapi/v1/depth", self.rest_url());
+
+        let resp = client
+            .get(&url)
+            .query(&[("symbol", symbol), ("limit", "1000")])
+            .send()
+            .await?;
+
+        let data = resp.json::<DepthSnapshot>().await?;
+        Ok(data)
+    }
+}
+
+/// Binance European Options API, only partial depth streams are available
+pub struct Options;
+
+impl Options {
+    pub fn rest_url(&self) -> &str {
+        "https://eapi.binance.com"
+    }
+
+    pub fn ws_url(&self) -> &str {
+        "wss://nbstream.binance.com/eoptions"
+    }
+
+    /// Symbols of every strike and side of one expiry, e.g. `BTCUSDT` and `240628`
+    pub async fn expiry_symbols(&self, underlying: &str, expiry: &str) -> Result<Vec<String>, reqwest::Error> {
+        let client = reqwest::Client::new();
+        let url = format!("{}/eapi/v1/exchangeInfo", self.rest_url());
+
+        let resp = client.get(&url).send().await?;
+        let data = resp.json::<OptionsExchangeInfo>().await?;
+
+        Ok(data
+            .option_symbols
+            .into_iter()
+            .filter(|s| s.underlying == underlying && s.symbol.split('-').nth(1) == Some(expiry))
+            .map(|s| s.symbol)
+            .collect())
+    }
+
+    /// Subscribe request for the depth stream of every strike of one expiry
+    pub async fn subscribe_expiry(&self, id: u64, underlying: &str, expiry: &str, levels: u32) -> Result<String, reqwest::Error> {
+        let streams: Vec<_> = self
+            .expiry_symbols(underlying, expiry)
+            .await?
+            .iter()
+            .map(|symbol| options_depth_stream(symbol, levels))
+            .collect();
+
+        Ok(subscribe(id, &streams))
+    }
+}
+
+impl Rest for Options {
+    type Error = reqwest::Error;
+
+    async fn get_orderbook(&self, symbol: &str) -> Result<DepthSnapshot, Self::Error> {
+        let client = reqwest::Client::new();
+        let url = format!("{}/eapi/v1/depth", self.rest_url());
+
+        let resp = client
+            .get(&url)
+            .query(&[("symbol", symbol), ("limit", "1000")])
+            .send()
+            .await?;
+
+        let data = resp.json::<DepthSnapshot>().await?;
+        Ok(data)
+    }
+}
+
+/// Options partial depth stream, `levels` is `10`, `20`, `50` or `100`
+pub fn options_depth_stream(symbol: &str, levels: u32) -> String {
+    format!("{}@depth{}@100ms", symbol, levels)
+}
+
+/// `SUBSCRIBE` request, answered with `{"result": null, "id": id}`
+pub fn subscribe(id: u64, streams: &[String]) -> String {
+    serde_json::json!({ "method": "SUBSCRIBE", "params": streams, "id": id })
+        .to_string()
+}
+
+/// `UNSUBSCRIBE` request, answered with `{"result": null, "id": id}`
+pub fn unsubscribe(id: u64, streams: &[String]) -> String {
+    serde_json::json!({ "method": "UNSUBSCRIBE", "params": streams, "id": id })
+        .to_string()
+}
+
+/// `LIST_SUBSCRIPTIONS` request, answered with the stream names in `result`
+pub fn list_subscriptions(id: u64) -> String {
+    serde_json::json!({ "method": "LIST_SUBSCRIPTIONS", "id": id })
+        .to_string()
+}
+
+/// Diff depth stream of a futures or spot symbol
+pub fn depth_stream(symbol: &str) -> String {
+    format!("{}@depth@100ms", symbol.to_lowercase())
+}
+
+/// Connection multiplexing `streams`, each payload is wrapped as `{"stream": ..., "data": ...}`
+pub fn combined_stream_url(ws_url: &str, streams: &[String]) -> String {
+    format!("{}/stream?streams={}", ws_url, streams.join("/"))
+}
@@ -1,11 +1,15 @@
-use super::types::DepthUpdateSeq;
+use super::decode::{DepthDecoder, SpotDepthDecoder};
+use super::types::{DepthUpdateSeq, OptionsDepthSeq, SpotDepthUpdateSeq};
 use crate::binance::api::Rest;
-use crate::binance::api::UM;
+use crate::binance::api::{CM, Spot, UM};
 use crate::l2_book::tokio::{Book as AsyncBook, SnapshotFetcher};
 use crate::l2_book::{BookSequencer, Order, PriceSize, Sequence};
+use crate::venue::Venue;
+use crate::ws::Message;
 use std::time::Duration;
 
-struct BinanceBookSequencer;
+/// Futures sequencer, each event chains on the previous one with `pu`
+pub struct BinanceBookSequencer;
 
 impl BookSequencer<DepthUpdateSeq> for BinanceBookSequencer {
     fn is_first_event(&self, cur_seq: Sequence, update: &Order<DepthUpdateSeq>) -> bool {
@@ -21,6 +25,23 @@ impl BookSequencer<DepthUpdateSeq> for BinanceBookSequencer {
     }
 }
 
+/// Spot sequencer, each event starts right after the previous `u`
+pub struct SpotBookSequencer;
+
+impl BookSequencer<SpotDepthUpdateSeq> for SpotBookSequencer {
+    fn is_first_event(&self, cur_seq: Sequence, update: &Order<SpotDepthUpdateSeq>) -> bool {
+        update.o.first_update_id <= cur_seq.val() + 1 && cur_seq.val() < update.o.last_update_id
+    }
+
+    fn is_stale(&self, cur_seq: Sequence, update: &Order<SpotDepthUpdateSeq>) -> bool {
+        cur_seq.val() + 1 < update.o.first_update_id
+    }
+
+    fn is_next(&self, cur_seq: Sequence, update: &Order<SpotDepthUpdateSeq>) -> bool {
+        cur_seq.val() + 1 == update.o.first_update_id
+    }
+}
+
 pub struct BinanceSnapshotFetcher<A> {
     api: A,
 }
@@ -38,8 +59,8 @@ impl<A: Rest + Sync> SnapshotFetcher<DepthUpdateSeq> for BinanceSnapshotFetcher<
             first_update_id: res.last_update_id,
             last_update_id: res.last_update_id,
             previous_update_id: res.last_update_id,
-            event_time_ms: res.event_time_ms,
-            transaction_time_ms: res.transaction_time_ms,
+            event_time_ms: res.event_time_ms.unwrap_or_default(),
+            transaction_time_ms: res.transaction_time_ms.unwrap_or_default(),
         };
 
         Ok(Order {
@@ -53,6 +74,68 @@ impl<A: Rest + Sync> SnapshotFetcher<DepthUpdateSeq> for BinanceSnapshotFetcher<
     }
 }
 
+impl<A: Rest + Sync> SnapshotFetcher<SpotDepthUpdateSeq> for BinanceSnapshotFetcher<A> {
+    type Error = A::Error;
+
+    async fn fetch_snapshot(&self, symbol: &str) -> Result<Order<SpotDepthUpdateSeq>, Self::Error> {
+        let res = self.api.get_orderbook(symbol).await?;
+
+        let bids = res.bids.into_iter().map(PriceSize::from).collect();
+        let asks = res.asks.into_iter().map(PriceSize::from).collect();
+
+        let seq = SpotDepthUpdateSeq {
+            first_update_id: res.last_update_id,
+            last_update_id: res.last_update_id,
+            event_time_ms: res.event_time_ms.unwrap_or_default(),
+        };
+
+        Ok(Order {
+            id: Sequence(res.last_update_id),
+            bids,
+            asks,
+            is_snapshot: true,
+            ts_ms: seq.event_time_ms,
+            o: seq,
+        })
+    }
+}
+
+/// Diff depth stream of one symbol on a raw `/ws` connection
+macro_rules! impl_venue {
+    ($api:ident, $seq:ty, $sequencer:ident, $decoder:ident) => {
+        impl Venue for $api {
+            type Seq = $seq;
+            type Sequencer = $sequencer;
+            type Fetcher = BinanceSnapshotFetcher<$api>;
+            type Decoder = $decoder;
+
+            fn stream_url(&self, symbol: &str) -> String {
+                format!("{}/ws/{}@depth@100ms", self.ws_url(), symbol.to_lowercase())
+            }
+
+            fn subscribe(&self, _symbol: &str) -> Vec<Message> {
+                vec![]
+            }
+
+            fn decoder(&self) -> Self::Decoder {
+                $decoder
+            }
+
+            fn sequencer(&self) -> Self::Sequencer {
+                $sequencer
+            }
+
+            fn fetcher(&self) -> Option<Self::Fetcher> {
+                Some(BinanceSnapshotFetcher { api: $api })
+            }
+        }
+    };
+}
+
+impl_venue!(UM, DepthUpdateSeq, BinanceBookSequencer, DepthDecoder);
+impl_venue!(CM, DepthUpdateSeq, BinanceBookSequencer, DepthDecoder);
+impl_venue!(Spot, SpotDepthUpdateSeq, SpotBookSequencer, SpotDepthDecoder);
+
 pub struct Book;
 
 impl Book {
@@ -65,4 +148,86 @@ impl Book {
             interval,
         )
     }
+
+    /// COIN-M delivery and perpetual book, sizes are in contracts.
+    /// See [`BookSnapshot::to_base`](crate::l2_book::fsm::BookSnapshot::to_base) for base and USD views.
+    pub fn new_cm(symbol: impl Into<String>, depth: usize, interval: Duration) -> AsyncBook<DepthUpdateSeq> {
+        AsyncBook::new(
+            symbol.into(),
+            BinanceBookSequencer,
+            BinanceSnapshotFetcher { api: CM },
+            depth,
+            interval,
+        )
+    }
+
+    pub fn new_spot(symbol: impl Into<String>, depth: usize, interval: Duration) -> AsyncBook<SpotDepthUpdateSeq> {
+        AsyncBook::new(
+            symbol.into(),
+            SpotBookSequencer,
+            BinanceSnapshotFetcher { api: Spot },
+            depth,
+            interval,
+        )
+    }
+
+    /// Options book, every partial depth push replaces it
+    pub fn new_options(symbol: impl Into<String>, depth: usize, interval: Duration) -> AsyncBook<OptionsDepthSeq> {
+        AsyncBook::new_snapshot_only(symbol.into(), depth, interval)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SpotBookSequencer;
+    use crate::binance::types::SpotDepthUpdateSeq;
+    use crate::l2_book::{BookAction, BookFsm, Order, Price, PriceSize, Sequence, Size};
+
+    fn order(first_update_id: u64, last_update_id: u64, is_snapshot: bool, bid: (f64, f64)) -> Order<SpotDepthUpdateSeq> {
+        Order {
+            id: Sequence(last_update_id),
+            bids: vec![PriceSize(Price::from(bid.0), Size::from(bid.1))],
+            asks: vec![],
+            is_snapshot,
+            ts_ms: 0,
+            o: SpotDepthUpdateSeq {
+                first_update_id,
+                last_update_id,
+                event_time_ms: 0,
+            },
+        }
+    }
+
+    #[test]
+    fn spot_first_event_and_next() {
+        let mut fsm = BookFsm::new(SpotBookSequencer);
+
+        assert_eq!(BookAction::RetrieveSnapshot, fsm.update(order(95, 105, false, (100.0, 1.0))));
+        assert_eq!(BookAction::Ok, fsm.update(order(90, 94, false, (100.0, 9.0))));
+        assert_eq!(BookAction::Ok, fsm.update(order(95, 105, false, (100.0, 1.0))));
+        assert_eq!(BookAction::Ok, fsm.update(order(106, 110, false, (100.0, 2.0))));
+
+        // Events ending at or before the snapshot are dropped, U <= 101 <= u is the first one
+        assert_eq!(BookAction::Ok, fsm.update(order(100, 100, true, (100.0, 5.0))));
+        assert_eq!(fsm.snapshot(1).bids, vec![(Price::from(100.0), Size::from(2.0))]);
+
+        // Next event starts right after the last `u`
+        assert_eq!(BookAction::Ok, fsm.update(order(111, 111, false, (100.0, 3.0))));
+        assert_eq!(fsm.snapshot(1).bids, vec![(Price::from(100.0), Size::from(3.0))]);
+    }
+
+    #[test]
+    fn spot_gaps_resync() {
+        let mut fsm = BookFsm::new(SpotBookSequencer);
+        fsm.update(order(100, 100, true, (100.0, 1.0)));
+
+        // First event starting past the snapshot
+        assert_eq!(BookAction::RetrieveSnapshot, fsm.update(order(102, 110, false, (100.0, 2.0))));
+
+        fsm.update(order(100, 100, true, (100.0, 1.0)));
+        assert_eq!(BookAction::Ok, fsm.update(order(101, 110, false, (100.0, 2.0))));
+
+        // Event skipping 111
+        assert_eq!(BookAction::RetrieveSnapshot, fsm.update(order(112, 115, false, (100.0, 3.0))));
+    }
 }
@@ -0,0 +1,248 @@
+use crate::feed::{Control, Decoder, Event, FeedError};
+use crate::l2_book::tokio::BookWriter;
+use crate::ws::WsHandle;
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+#[derive(Deserialize)]
+struct Envelope<'a> {
+    #[serde(borrow)]
+    stream: Option<&'a str>,
+    #[serde(borrow)]
+    data: Option<&'a RawValue>,
+}
+
+/// Unwraps the `{"stream": ..., "data": ...}` envelope of a `/stream` connection and
+/// decodes the payload in place with the raw stream decoder `D`.
+#[derive(Debug, Default)]
+pub struct CombinedDecoder<D> {
+    inner: D,
+}
+
+impl<D: Decoder> CombinedDecoder<D> {
+    pub fn new(inner: D) -> Self {
+        Self { inner }
+    }
+
+    /// Pushes the events of one frame and returns the stream they belong to,
+    /// `None` for request replies which aren't wrapped.
+    pub fn decode(&mut self, frame: &mut [u8], events: &mut Vec<Event<D::Seq>>) -> Option<String> {
+        let (stream, range) = match serde_json::from_slice::<Envelope>(frame) {
+            Ok(Envelope {
+                stream: Some(stream),
+                data: Some(data),
+            }) => {
+                // Payload is borrowed from the frame, decode it where it is
+                let start = data.get().as_ptr() as usize - frame.as_ptr() as usize;
+                (stream.to_string(), start..start + data.get().len())
+            }
+            Ok(_) => {
+                self.inner.decode(frame, events);
+                return None;
+            }
+            Err(e) => {
+                events.push(Event::decode_error(e));
+                return None;
+            }
+        };
+
+        self.inner.decode(&mut frame[range], events);
+        Some(stream)
+    }
+}
+
+/// Books of one connection keyed by stream name, e.g. `btcusdt@depth@100ms`.
+/// Streams can be added and removed while the router is running.
+pub struct Router<O> {
+    books: Arc<Mutex<HashMap<String, BookWriter<O>>>>,
+}
+
+impl<O> Clone for Router<O> {
+    fn clone(&self) -> Self {
+        Self {
+            books: self.books.clone(),
+        }
+    }
+}
+
+impl<O> Default for Router<O> {
+    fn default() -> Self {
+        Self {
+            books: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl<O> Router<O> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&self, stream: impl Into<String>, writer: BookWriter<O>) {
+        self.books.lock().unwrap().insert(stream.into(), writer);
+    }
+
+    pub fn remove(&self, stream: &str) -> Option<BookWriter<O>> {
+        self.books.lock().unwrap().remove(stream)
+    }
+
+    pub fn streams(&self) -> Vec<String> {
+        self.books.lock().unwrap().keys().cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.books.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes and returns every route
+    pub fn drain(&self) -> Vec<(String, BookWriter<O>)> {
+        self.books.lock().unwrap().drain().collect()
+    }
+
+    fn get(&self, stream: &str) -> Option<BookWriter<O>> {
+        self.books.lock().unwrap().get(stream).cloned()
+    }
+
+    fn writers(&self) -> Vec<BookWriter<O>> {
+        self.books.lock().unwrap().values().cloned().collect()
+    }
+}
+
+/// Event of a combined connection, `stream` is `None` for replies and connection errors
+#[derive(Debug)]
+pub struct StreamEvent<O> {
+    pub stream: Option<String>,
+    pub event: Event<O>,
+}
+
+/// Same as [`crate::feed::spawn_feed`] for a connection carrying many books, book events
+/// go to the writer routed to their stream. Every book is reset when the connection drops,
+/// their snapshot requests then share the weight budget of the REST API.
+pub fn spawn_router<D: Decoder>(ws: WsHandle, decoder: CombinedDecoder<D>, router: Router<D::Seq>) -> mpsc::Receiver<StreamEvent<D::Seq>> {
+    let (events_tx, events_rx) = mpsc::channel(1000);
+    tokio::spawn(route(ws, decoder, router, events_tx));
+    events_rx
+}
+
+async fn route<D: Decoder>(mut ws: WsHandle, mut decoder: CombinedDecoder<D>, router: Router<D::Seq>, events_tx: mpsc::Sender<StreamEvent<D::Seq>>) {
+    let mut events = Vec::new();
+
+    while let Some(res) = ws.rx.recv().await {
+        let mut frame = match res {
+            Ok(frame) => frame,
+            Err(e) => {
+                for writer in router.writers() {
+                    writer.reset().await;
+                }
+
+                let event = Event::Error(FeedError::Disconnected(e));
+                let _ = events_tx.try_send(StreamEvent { stream: None, event });
+                continue;
+            }
+        };
+
+        let stream = decoder.decode(&mut frame, &mut events);
+        let writer = stream.as_deref().and_then(|s| router.get(s));
+
+        for event in events.drain(..) {
+            let event = match (event, &writer) {
+                (Event::Book(order), Some(writer)) => {
+                    writer.update(order).await;
+                    continue;
+                }
+                (Event::Book(_), None) => Event::Error(FeedError::UnknownStream(stream.clone().unwrap_or_default())),
+                (Event::Control(Control::Reply(msg)), _) => {
+                    let _ = ws.tx.send(msg).await;
+                    continue;
+                }
+                (event, _) => event,
+            };
+
+            let _ = events_tx.try_send(StreamEvent {
+                stream: stream.clone(),
+                event,
+            });
+        }
+    }
+
+    let _ = events_tx
+        .send(StreamEvent {
+            stream: None,
+            event: Event::Error(FeedError::Closed),
+        })
+        .await;
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CombinedDecoder, Router, StreamEvent, spawn_router};
+    use crate::binance::decode::DepthDecoder;
+    use crate::feed::{Control, Event, FeedError};
+    use crate::l2_book::tokio::{BookMessage, BookWriter};
+    use crate::ws::{Stale, WsError, WsHandle};
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+
+    fn depth_frame(stream: &str) -> Vec<u8> {
+        format!(
+            r#"{{"stream":"{stream}","data":{{"e":"depthUpdate","E":1571889248277,"T":1571889248276,"s":"BTCUSDT","U":390497796,"u":390497878,"pu":390497794,"b":[["7403.89","0.002"]],"a":[]}}}}"#
+        )
+        .into_bytes()
+    }
+
+    #[test]
+    fn unwraps_envelope() {
+        let mut decoder = CombinedDecoder::new(DepthDecoder);
+        let mut events = vec![];
+
+        let mut frame = br#"{"stream":"btcusdt@depth@100ms","data":{"e":"depthUpdate","E":1571889248277,"T":1571889248276,"s":"BTCUSDT","U":390497796,"u":390497878,"pu":390497794,"b":[["7403.89","0.002"]],"a":[]}}"#.to_vec();
+        assert_eq!(decoder.decode(&mut frame, &mut events).as_deref(), Some("btcusdt@depth@100ms"));
+        assert!(matches!(events.pop(), Some(Event::Book(o)) if o.id.val() == 390497878));
+
+        let mut frame = br#"{"result":null,"id":1}"#.to_vec();
+        assert_eq!(decoder.decode(&mut frame, &mut events), None);
+        assert!(matches!(events.pop(), Some(Event::Control(Control::Message(_)))));
+
+        let mut frame = b"not json".to_vec();
+        assert_eq!(decoder.decode(&mut frame, &mut events), None);
+        assert!(matches!(events.pop(), Some(Event::Error(FeedError::Decode(_)))));
+    }
+
+    #[tokio::test]
+    async fn routes_by_stream() {
+        let (frames_tx, rx) = mpsc::channel(10);
+        let (tx, _requests) = mpsc::channel(10);
+
+        let router = Router::new();
+        let (btc, mut btc_rx) = BookWriter::channel();
+        let (eth, mut eth_rx) = BookWriter::channel();
+        router.insert("btcusdt@depth@100ms", btc);
+        router.insert("ethusdt@depth@100ms", eth);
+
+        let mut events = spawn_router(WsHandle { rx, tx }, CombinedDecoder::new(DepthDecoder), router);
+
+        frames_tx.send(Ok(depth_frame("btcusdt@depth@100ms"))).await.unwrap();
+        assert!(matches!(btc_rx.recv().await, Some(BookMessage::Update(o)) if o.id.val() == 390497878));
+
+        frames_tx.send(Ok(depth_frame("xrpusdt@depth@100ms"))).await.unwrap();
+        let StreamEvent { stream, event } = events.recv().await.unwrap();
+        assert_eq!(stream.as_deref(), Some("xrpusdt@depth@100ms"));
+        assert!(matches!(event, Event::Error(FeedError::UnknownStream(s)) if s == "xrpusdt@depth@100ms"));
+
+        frames_tx.send(Err(WsError::Stale(Stale::Read(Duration::ZERO)))).await.unwrap();
+        assert!(matches!(btc_rx.recv().await, Some(BookMessage::Reset)));
+        assert!(matches!(eth_rx.recv().await, Some(BookMessage::Reset)));
+        let event = events.recv().await.unwrap().event;
+        assert!(matches!(event, Event::Error(FeedError::Disconnected(_))));
+
+        drop(frames_tx);
+        assert!(matches!(events.recv().await.unwrap().event, Event::Error(FeedError::Closed)));
+    }
+}
@@ -0,0 +1,92 @@
+use super::types::{AggTrade, DepthUpdate, DepthUpdateSeq, SpotDepthUpdate, SpotDepthUpdateSeq};
+use crate::feed::{Control, Decoder, Event, FeedError, TakerSide, Trade};
+use crate::l2_book::Order;
+use serde::Deserialize;
+
+/// Futures `depthUpdate`, `trade` and `aggTrade` events of a raw `/ws` stream
+#[derive(Debug, Default)]
+pub struct DepthDecoder;
+
+/// Spot `depthUpdate`, `trade` and `aggTrade` events of a raw `/ws` stream
+#[derive(Debug, Default)]
+pub struct SpotDepthDecoder;
+
+impl Decoder for DepthDecoder {
+    type Seq = DepthUpdateSeq;
+
+    fn decode(&mut self, frame: &mut [u8], events: &mut Vec<Event<DepthUpdateSeq>>) {
+        decode::<DepthUpdate, _>(frame, events)
+    }
+}
+
+impl Decoder for SpotDepthDecoder {
+    type Seq = SpotDepthUpdateSeq;
+
+    fn decode(&mut self, frame: &mut [u8], events: &mut Vec<Event<SpotDepthUpdateSeq>>) {
+        decode::<SpotDepthUpdate, _>(frame, events)
+    }
+}
+
+#[derive(Deserialize)]
+struct EventType<'a> {
+    #[serde(rename = "e")]
+    #[serde(borrow)]
+    event_type: Option<std::borrow::Cow<'a, str>>,
+}
+
+/// Events are told apart by `e`, request replies have none
+fn decode<'a, U, O>(frame: &'a [u8], events: &mut Vec<Event<O>>)
+where
+    U: Deserialize<'a> + Into<Order<O>>,
+{
+    let event_type = match serde_json::from_slice::<EventType>(frame) {
+        Ok(e) => e.event_type,
+        Err(e) => return events.push(Event::decode_error(e)),
+    };
+
+    let event = match event_type.as_deref() {
+        Some("depthUpdate") => serde_json::from_slice::<U>(frame).map(|u| Event::Book(u.into())),
+        Some("trade" | "aggTrade") => serde_json::from_slice::<AggTrade>(frame).map(|t| Event::Trade(t.into())),
+        Some(other) => Ok(Event::Error(FeedError::UnknownEvent(other.to_string()))),
+        None => Ok(Event::Control(Control::Message(frame.to_vec()))),
+    };
+
+    events.push(event.unwrap_or_else(Event::decode_error));
+}
+
+impl From<AggTrade> for Trade {
+    fn from(val: AggTrade) -> Self {
+        Trade {
+            price: val.price,
+            size: val.size,
+            side: if val.is_buyer_maker { TakerSide::Sell } else { TakerSide::Buy },
+            ts_ms: val.trade_time_ms,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DepthDecoder;
+    use crate::feed::{Control, Decoder, Event, FeedError, TakerSide};
+
+    fn decode(frame: &str) -> Event<crate::binance::types::DepthUpdateSeq> {
+        let mut events = vec![];
+        DepthDecoder.decode(&mut frame.as_bytes().to_vec(), &mut events);
+        assert_eq!(events.len(), 1);
+        events.pop().unwrap()
+    }
+
+    #[test]
+    fn decode_events() {
+        let d = r#"{"e":"depthUpdate","E":1571889248277,"T":1571889248276,"s":"BTCUSDT","U":390497796,"u":390497878,"pu":390497794,"b":[["7403.89","0.002"]],"a":[]}"#;
+        assert!(matches!(decode(d), Event::Book(o) if o.id.val() == 390497878));
+
+        let d = r#"{"e":"aggTrade","E":123456789,"s":"BTCUSDT","a":5933014,"p":"0.001","q":"100","f":100,"l":105,"T":123456785,"m":true}"#;
+        assert!(matches!(decode(d), Event::Trade(t) if t.side == TakerSide::Sell && t.ts_ms == 123456785));
+
+        assert!(matches!(decode(r#"{"e":"markPriceUpdate"}"#), Event::Error(FeedError::UnknownEvent(e)) if e == "markPriceUpdate"));
+        assert!(matches!(decode(r#"{"result":null,"id":1}"#), Event::Control(Control::Message(_))));
+        assert!(matches!(decode("not json"), Event::Error(FeedError::Decode(_))));
+    }
+}
@@ -1,5 +1,9 @@
 pub mod api;
 pub mod book;
+pub mod combined;
+pub mod decode;
+pub mod pool;
+pub mod subscription;
 pub mod types;
 
 pub use book::Book;
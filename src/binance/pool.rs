@@ -0,0 +1,475 @@
+use super::combined::{CombinedDecoder, Router, StreamEvent, spawn_router};
+use super::subscription::{SubscriptionError, Subscriptions};
+use crate::feed::{Control, Decoder, Event, FeedError};
+use crate::l2_book::tokio::BookWriter;
+use crate::ws::{self, Backoff, Message, WsHandle};
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+/// Limits of one pool, Binance allows 200 streams per futures connection and 1024 on spot
+#[derive(Debug, Clone, Copy)]
+pub struct PoolOptions {
+    pub max_streams: usize,
+    pub max_connections: usize,
+    /// Reconnects of each connection, its books move to other connections once it gives up
+    /// after `max_attempts`. Defaults to 10 attempts.
+    pub backoff: Backoff,
+}
+
+impl Default for PoolOptions {
+    fn default() -> Self {
+        Self {
+            max_streams: 200,
+            max_connections: 50,
+            backoff: Backoff {
+                max_attempts: Some(10),
+                ..Backoff::default()
+            },
+        }
+    }
+}
+
+impl PoolOptions {
+    pub fn max_streams(mut self, max_streams: usize) -> Self {
+        self.max_streams = max_streams;
+        self
+    }
+
+    pub fn max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = max_connections;
+        self
+    }
+
+    pub fn backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PoolError {
+    /// Streams don't fit within `max_streams` times `max_connections`
+    Full,
+    Subscription(SubscriptionError),
+}
+
+impl fmt::Display for PoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PoolError::Full => write!(f, "Pool is full"),
+            PoolError::Subscription(e) => write!(f, "Subscription error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PoolError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PoolError::Subscription(e) => Some(e),
+            PoolError::Full => None,
+        }
+    }
+}
+
+impl From<SubscriptionError> for PoolError {
+    fn from(value: SubscriptionError) -> Self {
+        PoolError::Subscription(value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionLoad {
+    pub id: usize,
+    pub streams: usize,
+    /// Whether frames are flowing, `false` while connecting or reconnecting
+    pub connected: bool,
+}
+
+struct Connection<O> {
+    id: usize,
+    router: Router<O>,
+    subs: Subscriptions,
+    connected: Arc<AtomicBool>,
+}
+
+struct State<O> {
+    next_id: usize,
+    connections: Vec<Connection<O>>,
+}
+
+/// Books of many streams sharded over as few combined-stream connections as the limits allow.
+///
+/// Each connection reconnects on its own and restores its subscriptions. Once one gives up
+/// after `backoff.max_attempts`, its books are moved to connections with room left.
+pub struct Pool<D: Decoder> {
+    ws_url: Arc<str>,
+    opts: PoolOptions,
+    state: Arc<Mutex<State<D::Seq>>>,
+    events_tx: mpsc::Sender<StreamEvent<D::Seq>>,
+    decoder: PhantomData<fn() -> D>,
+}
+
+impl<D: Decoder> Clone for Pool<D> {
+    fn clone(&self) -> Self {
+        Self {
+            ws_url: self.ws_url.clone(),
+            opts: self.opts,
+            state: self.state.clone(),
+            events_tx: self.events_tx.clone(),
+            decoder: PhantomData,
+        }
+    }
+}
+
+impl<D: Decoder + Default> Pool<D> {
+    /// Empty pool connecting to `ws_url`, e.g. [`super::api::UM::ws_url`], each connection
+    /// decodes with its own `D`. Events of every connection come out of the receiver.
+    pub fn new(ws_url: &str, opts: PoolOptions) -> (Self, mpsc::Receiver<StreamEvent<D::Seq>>) {
+        let (events_tx, events_rx) = mpsc::channel(1000);
+
+        let pool = Self {
+            ws_url: ws_url.into(),
+            opts,
+            state: Arc::new(Mutex::new(State {
+                next_id: 0,
+                connections: Vec::new(),
+            })),
+            events_tx,
+            decoder: PhantomData,
+        };
+
+        (pool, events_rx)
+    }
+
+    /// Places every book on the least loaded connection with room, opening new connections
+    /// as needed, and resolves once all subscriptions are acknowledged. Nothing stays placed
+    /// if the books don't fit or a subscription fails, streams already sent are unsubscribed.
+    pub async fn add(&self, books: Vec<(String, BookWriter<D::Seq>)>) -> Result<(), PoolError> {
+        let batches = self.place(books)?;
+
+        for (i, (subs, streams)) in batches.iter().enumerate() {
+            if let Err(e) = subs.subscribe(streams).await {
+                let placed: Vec<_> = batches.iter().flat_map(|(_, streams)| streams.iter().cloned()).collect();
+                self.unroute(&placed);
+
+                // The failed request may still be restored after a disconnect
+                for (subs, streams) in &batches[..=i] {
+                    let _ = subs.unsubscribe(streams).await;
+                }
+
+                return Err(e.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn remove(&self, streams: &[String]) -> Result<(), PoolError> {
+        for (subs, streams) in self.unroute(streams) {
+            subs.unsubscribe(&streams).await?;
+        }
+
+        Ok(())
+    }
+
+    pub fn load(&self) -> Vec<ConnectionLoad> {
+        let state = self.state.lock().unwrap();
+        state
+            .connections
+            .iter()
+            .map(|conn| ConnectionLoad {
+                id: conn.id,
+                streams: conn.router.len(),
+                connected: conn.connected.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+
+    /// Removes the routes of `streams`, grouped by the connection they were on
+    fn unroute(&self, streams: &[String]) -> Vec<(Subscriptions, Vec<String>)> {
+        let state = self.state.lock().unwrap();
+        state
+            .connections
+            .iter()
+            .filter_map(|conn| {
+                let removed: Vec<_> = streams.iter().filter(|s| conn.router.remove(s).is_some()).cloned().collect();
+                (!removed.is_empty()).then(|| (conn.subs.clone(), removed))
+            })
+            .collect()
+    }
+
+    fn place(&self, books: Vec<(String, BookWriter<D::Seq>)>) -> Result<Vec<(Subscriptions, Vec<String>)>, PoolError> {
+        let mut state = self.state.lock().unwrap();
+
+        let max_streams = self.opts.max_streams;
+        let free: usize = state.connections.iter().map(|c| max_streams.saturating_sub(c.router.len())).sum::<usize>()
+            + self.opts.max_connections.saturating_sub(state.connections.len()) * max_streams;
+
+        if books.len() > free {
+            return Err(PoolError::Full);
+        }
+
+        let mut batches: HashMap<usize, Vec<String>> = HashMap::new();
+        for (stream, writer) in books {
+            let least_loaded = state
+                .connections
+                .iter()
+                .filter(|c| c.router.len() < max_streams)
+                .min_by_key(|c| c.router.len());
+
+            let conn = match least_loaded {
+                Some(conn) => conn,
+                None => {
+                    let conn = self.open(&mut state);
+                    state.connections.push(conn);
+                    state.connections.last().unwrap()
+                }
+            };
+
+            conn.router.insert(stream.clone(), writer);
+            batches.entry(conn.id).or_default().push(stream);
+        }
+
+        Ok(state
+            .connections
+            .iter()
+            .filter_map(|conn| Some((conn.subs.clone(), batches.remove(&conn.id)?)))
+            .collect())
+    }
+
+    fn open(&self, state: &mut State<D::Seq>) -> Connection<D::Seq> {
+        state.next_id += 1;
+
+        // Requests queue up here until the connection is open
+        let (tx, rx) = mpsc::channel(100);
+        let conn = Connection {
+            id: state.next_id,
+            router: Router::new(),
+            subs: Subscriptions::new(tx),
+            connected: Arc::new(AtomicBool::new(false)),
+        };
+
+        tokio::spawn(supervise(
+            self.clone(),
+            conn.id,
+            conn.router.clone(),
+            conn.subs.clone(),
+            conn.connected.clone(),
+            rx,
+        ));
+
+        conn
+    }
+
+    /// Moves the books of a connection that gave up onto the others
+    async fn rebalance(&self, id: usize) {
+        let books = {
+            let mut state = self.state.lock().unwrap();
+            let Some(pos) = state.connections.iter().position(|c| c.id == id) else {
+                return;
+            };
+
+            state.connections.remove(pos).router.drain()
+        };
+
+        let streams: Vec<_> = books.iter().map(|(s, _)| s.clone()).collect();
+        if self.add(books).await.is_err() {
+            for stream in streams {
+                let event = Event::Error(FeedError::Closed);
+                // Terminal for the book, so it waits for room as the Closed of a feed does
+                let _ = self.events_tx.send(StreamEvent { stream: Some(stream), event }).await;
+            }
+        }
+    }
+}
+
+/// Runs one connection until it gives up, then hands its books back to the pool
+async fn supervise<D: Decoder + Default>(
+    pool: Pool<D>,
+    id: usize,
+    router: Router<D::Seq>,
+    subs: Subscriptions,
+    connected: Arc<AtomicBool>,
+    mut requests: mpsc::Receiver<Message>,
+) {
+    let url = format!("{}/stream", pool.ws_url);
+
+    if let Some(ws) = open(&url, pool.opts.backoff, &pool.events_tx).await {
+        let ws_tx = ws.tx.clone();
+        let mut events = spawn_router(ws, CombinedDecoder::new(D::default()), router);
+
+        loop {
+            tokio::select! {
+                Some(msg) = requests.recv() => {
+                    let _ = ws_tx.send(msg).await;
+                }
+                event = events.recv() => {
+                    let Some(StreamEvent { stream, event }) = event else { break };
+
+                    match event {
+                        Event::Control(Control::Message(msg)) if subs.handle_reply(&msg) => continue,
+                        Event::Error(FeedError::Closed) => break,
+                        Event::Error(FeedError::Disconnected(_)) => {
+                            connected.store(false, Ordering::Relaxed);
+                            subs.restore();
+                        }
+                        _ => connected.store(true, Ordering::Relaxed),
+                    }
+
+                    let _ = pool.events_tx.try_send(StreamEvent { stream, event });
+                }
+            }
+        }
+    }
+
+    connected.store(false, Ordering::Relaxed);
+    pool.rebalance(id).await;
+}
+
+/// First connection attempt, retried like the reconnects
+async fn open<O>(url: &str, backoff: Backoff, events_tx: &mpsc::Sender<StreamEvent<O>>) -> Option<WsHandle> {
+    let mut attempt = 0;
+
+    loop {
+        match ws::connect_reconnecting(url, vec![], backoff).await {
+            Ok(ws) => return Some(ws),
+            Err(e) => {
+                let event = Event::Error(FeedError::Disconnected(e));
+                let _ = events_tx.try_send(StreamEvent { stream: None, event });
+            }
+        }
+
+        if backoff.max_attempts.is_some_and(|max| attempt >= max) {
+            return None;
+        }
+
+        tokio::time::sleep(backoff.delay(attempt)).await;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Connection, Pool, PoolError, PoolOptions};
+    use crate::binance::combined::Router;
+    use crate::binance::decode::DepthDecoder;
+    use crate::binance::subscription::{SubscriptionError, Subscriptions};
+    use crate::binance::types::DepthUpdateSeq;
+    use crate::feed::{Event, FeedError};
+    use crate::l2_book::tokio::BookWriter;
+    use crate::ws::Message;
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicBool;
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+
+    fn books(symbols: &[&str]) -> Vec<(String, BookWriter<DepthUpdateSeq>)> {
+        symbols
+            .iter()
+            .map(|s| (s.to_string(), crate::binance::Book::new_um(*s, 10, Duration::ZERO).writer()))
+            .collect()
+    }
+
+    /// Connection without a socket, its requests come out of the receiver
+    fn connection(pool: &Pool<DepthDecoder>) -> (Subscriptions, mpsc::Receiver<Message>) {
+        let (tx, rx) = mpsc::channel(10);
+        let subs = Subscriptions::with_rate(tx, 1000);
+
+        let mut state = pool.state.lock().unwrap();
+        state.next_id += 1;
+        let conn = Connection {
+            id: state.next_id,
+            router: Router::new(),
+            subs: subs.clone(),
+            connected: Arc::new(AtomicBool::new(true)),
+        };
+        state.connections.push(conn);
+
+        (subs, rx)
+    }
+
+    async fn reply(subs: &Subscriptions, rx: &mut mpsc::Receiver<Message>, result: &str) -> String {
+        let Some(Message::Text(text)) = rx.recv().await else {
+            panic!("expected a request");
+        };
+        let request: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert!(subs.handle_reply(format!(r#"{{{},"id":{}}}"#, result, request["id"]).as_bytes()));
+        request["method"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn shards_within_limits() {
+        let opts = PoolOptions::default().max_streams(2).max_connections(2);
+        let (pool, _events) = Pool::<DepthDecoder>::new("wss://127.0.0.1:1", opts);
+
+        pool.place(books(&["a", "b", "c"])).unwrap();
+        let load: Vec<_> = pool.load().iter().map(|l| l.streams).collect();
+        assert_eq!(load, vec![2, 1]);
+
+        assert!(matches!(pool.place(books(&["d", "e"])), Err(PoolError::Full)));
+        pool.place(books(&["d"])).unwrap();
+        assert!(pool.load().iter().all(|l| l.streams == 2 && !l.connected));
+    }
+
+    #[tokio::test]
+    async fn failed_add_is_rolled_back() {
+        let opts = PoolOptions::default().max_streams(2).max_connections(1);
+        let (pool, _events) = Pool::<DepthDecoder>::new("wss://127.0.0.1:1", opts);
+        let (subs, mut rx) = connection(&pool);
+
+        let add = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.add(books(&["a", "b"])).await }
+        });
+
+        assert_eq!(reply(&subs, &mut rx, r#""code":-1121,"msg":"Invalid symbol.""#).await, "SUBSCRIBE");
+        assert_eq!(reply(&subs, &mut rx, r#""result":null"#).await, "UNSUBSCRIBE");
+
+        let err = add.await.unwrap().unwrap_err();
+        assert!(matches!(err, PoolError::Subscription(SubscriptionError::Api { code: -1121, .. })));
+        assert_eq!(pool.load()[0].streams, 0);
+    }
+
+    #[tokio::test]
+    async fn rebalances_books_of_lost_connection() {
+        assert!(PoolOptions::default().backoff.max_attempts.is_some());
+
+        let opts = PoolOptions::default().max_streams(2).max_connections(2);
+        let (pool, mut events) = Pool::<DepthDecoder>::new("wss://127.0.0.1:1", opts);
+        let (_, _lost) = connection(&pool);
+        let (subs, mut rx) = connection(&pool);
+        for (stream, writer) in books(&["a", "b"]) {
+            pool.state.lock().unwrap().connections[0].router.insert(stream, writer);
+        }
+
+        let rebalance = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.rebalance(1).await }
+        });
+
+        assert_eq!(reply(&subs, &mut rx, r#""result":null"#).await, "SUBSCRIBE");
+        rebalance.await.unwrap();
+        assert_eq!(pool.load().iter().map(|l| (l.id, l.streams)).collect::<Vec<_>>(), vec![(2, 2)]);
+
+        // Books that can't be moved are closed
+        let (subs, mut rx) = connection(&pool);
+        let rebalance = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.rebalance(2).await }
+        });
+
+        assert_eq!(reply(&subs, &mut rx, r#""code":-1,"msg":"Too many requests""#).await, "SUBSCRIBE");
+        assert_eq!(reply(&subs, &mut rx, r#""result":null"#).await, "UNSUBSCRIBE");
+        rebalance.await.unwrap();
+
+        let mut closed: Vec<_> = (0..2).map(|_| events.try_recv().unwrap()).collect();
+        closed.sort_by(|a, b| a.stream.cmp(&b.stream));
+        assert_eq!(closed[0].stream.as_deref(), Some("a"));
+        assert!(closed.iter().all(|e| matches!(e.event, Event::Error(FeedError::Closed))));
+        assert_eq!(pool.load()[0].streams, 0);
+    }
+}
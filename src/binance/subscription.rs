@@ -0,0 +1,294 @@
+use super::api;
+use super::types::RequestReply;
+use crate::ws::Message;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::MissedTickBehavior;
+
+/// Incoming messages Binance accepts per second and connection, pings and pongs included
+pub const MESSAGE_RATE_LIMIT: u32 = 10;
+
+/// Requests per second sent by [`Subscriptions::new`], half of [`MESSAGE_RATE_LIMIT`] is left
+/// to pings, pongs and other messages of the connection
+pub const DEFAULT_REQUEST_RATE: u32 = MESSAGE_RATE_LIMIT / 2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubscriptionError {
+    /// Request rejected by Binance
+    Api { code: i64, msg: String },
+    /// Connection dropped before the request was answered
+    Disconnected,
+    /// Connection handle is gone
+    Closed,
+}
+
+impl fmt::Display for SubscriptionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubscriptionError::Api { code, msg } => write!(f, "Request rejected ({}): {}", code, msg),
+            SubscriptionError::Disconnected => write!(f, "Disconnected before reply"),
+            SubscriptionError::Closed => write!(f, "Connection closed"),
+        }
+    }
+}
+
+impl std::error::Error for SubscriptionError {}
+
+enum Request {
+    Subscribe(Vec<String>),
+    Unsubscribe(Vec<String>),
+    List,
+}
+
+struct Pending {
+    request: Request,
+    reply: oneshot::Sender<Result<serde_json::Value, SubscriptionError>>,
+}
+
+#[derive(Default)]
+struct State {
+    next_id: u64,
+    pending: HashMap<u64, Pending>,
+    active: BTreeSet<String>,
+    /// Id of the last restore request
+    restoring: Option<u64>,
+}
+
+/// Tracks `SUBSCRIBE`, `UNSUBSCRIBE` and `LIST_SUBSCRIPTIONS` requests of one connection.
+///
+/// Requests are paced to `rate` messages per second and resolve once the matching reply
+/// is passed to [`Subscriptions::handle_reply`], so they must not be awaited from the task
+/// reading the connection. Acknowledged streams are kept to be restored after a reconnect.
+#[derive(Clone)]
+pub struct Subscriptions {
+    state: Arc<Mutex<State>>,
+    requests: mpsc::UnboundedSender<Message>,
+}
+
+impl Subscriptions {
+    /// Sends requests on `tx`, usually [`crate::ws::WsHandle::tx`], at [`DEFAULT_REQUEST_RATE`]
+    pub fn new(tx: mpsc::Sender<Message>) -> Self {
+        Self::with_rate(tx, DEFAULT_REQUEST_RATE)
+    }
+
+    /// Lower `rate` to leave room for other messages sent on the connection
+    pub fn with_rate(tx: mpsc::Sender<Message>, rate: u32) -> Self {
+        let (requests, requests_rx) = mpsc::unbounded_channel();
+        tokio::spawn(pace(requests_rx, tx, Duration::from_secs(1) / rate.max(1)));
+
+        Self {
+            state: Arc::new(Mutex::new(State::default())),
+            requests,
+        }
+    }
+
+    pub async fn subscribe(&self, streams: &[String]) -> Result<(), SubscriptionError> {
+        self.request(Request::Subscribe(streams.to_vec())).await.map(|_| ())
+    }
+
+    pub async fn unsubscribe(&self, streams: &[String]) -> Result<(), SubscriptionError> {
+        self.request(Request::Unsubscribe(streams.to_vec())).await.map(|_| ())
+    }
+
+    /// Streams the venue reports as subscribed
+    pub async fn list(&self) -> Result<Vec<String>, SubscriptionError> {
+        let result = self.request(Request::List).await?;
+        Ok(serde_json::from_value(result).unwrap_or_default())
+    }
+
+    /// Acknowledged streams
+    pub fn active(&self) -> Vec<String> {
+        self.state.lock().unwrap().active.iter().cloned().collect()
+    }
+
+    /// Fails requests of the dropped connection and subscribes the active streams again,
+    /// call it whenever the connection reports a disconnect. Streams of unanswered requests
+    /// are restored as if they had been acknowledged.
+    ///
+    /// Nothing is done while the previous restore is unanswered, so the errors of failed
+    /// reconnection attempts restore only once per lost connection.
+    pub fn restore(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.restoring.is_some_and(|id| state.pending.contains_key(&id)) {
+            return;
+        }
+
+        let mut requested = Vec::new();
+
+        for (_, pending) in std::mem::take(&mut state.pending) {
+            match pending.request {
+                Request::Subscribe(streams) => requested.extend(streams),
+                Request::Unsubscribe(streams) => streams.iter().for_each(|s| {
+                    state.active.remove(s);
+                }),
+                Request::List => {}
+            }
+
+            let _ = pending.reply.send(Err(SubscriptionError::Disconnected));
+        }
+
+        let streams: BTreeSet<_> = state.active.iter().cloned().chain(requested).collect();
+        if streams.is_empty() {
+            return;
+        }
+
+        // Nobody waits on the reply, the streams are kept active either way
+        let (reply, _) = oneshot::channel();
+        if self.send(&mut state, Request::Subscribe(streams.into_iter().collect()), reply).is_ok() {
+            state.restoring = Some(state.next_id);
+        }
+    }
+
+    /// Resolves the request answered by `frame`, returns `false` if it isn't a reply
+    /// to a tracked request.
+    pub fn handle_reply(&self, frame: &[u8]) -> bool {
+        let Ok(reply) = serde_json::from_slice::<RequestReply>(frame) else {
+            return false;
+        };
+
+        let (id, result) = match reply {
+            RequestReply::Result { result, id } => (id, Ok(result)),
+            RequestReply::Error { code, msg, id: Some(id) } => (id, Err(SubscriptionError::Api { code, msg })),
+            RequestReply::Error { id: None, .. } => return false,
+        };
+
+        let mut state = self.state.lock().unwrap();
+        let Some(pending) = state.pending.remove(&id) else {
+            return false;
+        };
+
+        if result.is_ok() {
+            match pending.request {
+                Request::Subscribe(streams) => state.active.extend(streams),
+                Request::Unsubscribe(streams) => streams.iter().for_each(|s| {
+                    state.active.remove(s);
+                }),
+                Request::List => {}
+            }
+        }
+
+        let _ = pending.reply.send(result);
+        true
+    }
+
+    async fn request(&self, request: Request) -> Result<serde_json::Value, SubscriptionError> {
+        let (reply, reply_rx) = oneshot::channel();
+        self.send(&mut self.state.lock().unwrap(), request, reply)?;
+        reply_rx.await.map_err(|_| SubscriptionError::Closed)?
+    }
+
+    fn send(
+        &self,
+        state: &mut State,
+        request: Request,
+        reply: oneshot::Sender<Result<serde_json::Value, SubscriptionError>>,
+    ) -> Result<(), SubscriptionError> {
+        state.next_id += 1;
+        let id = state.next_id;
+
+        let msg = match &request {
+            Request::Subscribe(streams) => api::subscribe(id, streams),
+            Request::Unsubscribe(streams) => api::unsubscribe(id, streams),
+            Request::List => api::list_subscriptions(id),
+        };
+
+        self.requests.send(msg.into()).map_err(|_| SubscriptionError::Closed)?;
+        state.pending.insert(id, Pending { request, reply });
+        Ok(())
+    }
+}
+
+/// Forwards requests at most once per `period`
+async fn pace(mut requests: mpsc::UnboundedReceiver<Message>, tx: mpsc::Sender<Message>, period: Duration) {
+    let mut interval = tokio::time::interval(period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    while let Some(msg) = requests.recv().await {
+        interval.tick().await;
+        if tx.send(msg).await.is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SubscriptionError, Subscriptions};
+    use crate::ws::Message;
+    use tokio::sync::mpsc;
+
+    async fn request(rx: &mut mpsc::Receiver<Message>) -> serde_json::Value {
+        match rx.recv().await {
+            Some(Message::Text(text)) => serde_json::from_str(&text).unwrap(),
+            msg => panic!("expected a text request, got {:?}", msg),
+        }
+    }
+
+    async fn request_id(rx: &mut mpsc::Receiver<Message>) -> u64 {
+        request(rx).await["id"].as_u64().unwrap()
+    }
+
+    #[tokio::test]
+    async fn correlates_replies() {
+        let (tx, mut rx) = mpsc::channel(10);
+        let subs = Subscriptions::new(tx);
+
+        let streams = vec!["btcusdt@depth".to_string(), "ethusdt@depth".to_string()];
+        let req = tokio::spawn({
+            let subs = subs.clone();
+            async move { subs.subscribe(&streams).await }
+        });
+
+        let id = request_id(&mut rx).await;
+        assert!(!subs.handle_reply(format!(r#"{{"result":null,"id":{}}}"#, id + 1).as_bytes()));
+        assert!(subs.handle_reply(format!(r#"{{"result":null,"id":{}}}"#, id).as_bytes()));
+        assert_eq!(req.await.unwrap(), Ok(()));
+        assert_eq!(subs.active(), vec!["btcusdt@depth", "ethusdt@depth"]);
+
+        let req = tokio::spawn({
+            let subs = subs.clone();
+            async move { subs.unsubscribe(&["btcusdt@depth".to_string()]).await }
+        });
+
+        let id = request_id(&mut rx).await;
+        assert!(subs.handle_reply(format!(r#"{{"code":2,"msg":"Invalid request","id":{}}}"#, id).as_bytes()));
+        assert!(matches!(req.await.unwrap(), Err(SubscriptionError::Api { code: 2, .. })));
+        assert_eq!(subs.active().len(), 2);
+
+        // Active streams are subscribed again after a reconnect
+        subs.restore();
+        let msg = request(&mut rx).await;
+        assert_eq!(msg["method"], "SUBSCRIBE");
+        assert_eq!(msg["params"].as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn restores_once_per_outage() {
+        let (tx, mut rx) = mpsc::channel(10);
+        let subs = Subscriptions::with_rate(tx, 1000);
+
+        let req = tokio::spawn({
+            let subs = subs.clone();
+            async move { subs.subscribe(&["btcusdt@depth".to_string()]).await }
+        });
+
+        let id = request_id(&mut rx).await;
+        assert!(subs.handle_reply(format!(r#"{{"result":null,"id":{}}}"#, id).as_bytes()));
+        assert_eq!(req.await.unwrap(), Ok(()));
+
+        // Every failed reconnection attempt reports a disconnect
+        subs.restore();
+        subs.restore();
+        let id = request_id(&mut rx).await;
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        assert!(rx.try_recv().is_err());
+
+        // Answered, the next disconnect is a new outage
+        assert!(subs.handle_reply(format!(r#"{{"result":null,"id":{}}}"#, id).as_bytes()));
+        subs.restore();
+        assert_eq!(request(&mut rx).await["method"], "SUBSCRIBE");
+    }
+}
@@ -63,19 +63,173 @@ pub struct DepthUpdateSeq {
     pub transaction_time_ms: u64,
 }
 
+/// Spot `depthUpdate` event, which carries no `pu` nor `T`
 #[derive(Serialize, Deserialize, Debug)]
-pub struct DepthSnapshot {
-    #[serde(rename = "lastUpdateId")]
+pub struct SpotDepthUpdate<'a> {
+    #[serde(rename = "e")]
+    #[serde(borrow)]
+    pub event_type: std::borrow::Cow<'a, str>,
+
+    #[serde(rename = "s")]
+    #[serde(borrow)]
+    pub symbol: std::borrow::Cow<'a, str>,
+
+    #[serde(rename = "b")]
+    pub bids: Vec<PriceSize>,
+
+    #[serde(rename = "a")]
+    pub asks: Vec<PriceSize>,
+
+    #[serde(flatten)]
+    pub seq: SpotDepthUpdateSeq,
+}
+
+impl<'a> From<SpotDepthUpdate<'a>> for l2_book::Order<SpotDepthUpdateSeq> {
+    fn from(val: SpotDepthUpdate<'a>) -> Self {
+        let bids = val.bids.into_iter().map(Into::into).collect();
+        let asks = val.asks.into_iter().map(Into::into).collect();
+        let seq = val.seq;
+
+        l2_book::Order {
+            id: l2_book::Sequence(seq.last_update_id),
+            bids,
+            asks,
+            is_snapshot: false,
+            ts_ms: seq.event_time_ms,
+            o: seq,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpotDepthUpdateSeq {
+    #[serde(rename = "U")]
+    pub first_update_id: u64,
+
+    #[serde(rename = "u")]
     pub last_update_id: u64,
 
     #[serde(rename = "E")]
     pub event_time_ms: u64,
+}
+
+/// Depth snapshot, `E` is only sent by the futures APIs and `T` by futures and options.
+/// Options name the update id `u`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DepthSnapshot {
+    #[serde(rename = "lastUpdateId", alias = "u")]
+    pub last_update_id: u64,
+
+    #[serde(rename = "E")]
+    pub event_time_ms: Option<u64>,
 
     #[serde(rename = "T")]
-    pub transaction_time_ms: u64,
+    pub transaction_time_ms: Option<u64>,
+
+    pub bids: Vec<PriceSize>,
+    pub asks: Vec<PriceSize>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExchangeInfo {
+    pub symbols: Vec<SymbolInfo>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SymbolInfo {
+    pub symbol: String,
+
+    #[serde(rename = "contractSize")]
+    pub contract_size: f64,
+}
+
+/// `aggTrade` event, `trade` events carry the same fields
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AggTrade {
+    #[serde(rename = "p")]
+    pub price: l2_book::Price,
+
+    #[serde(rename = "q")]
+    pub size: l2_book::Size,
+
+    #[serde(rename = "T")]
+    pub trade_time_ms: u64,
+
+    #[serde(rename = "m")]
+    pub is_buyer_maker: bool,
+}
+
+/// Options partial depth push, each one is the full top of the book
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OptionsDepth<'a> {
+    #[serde(rename = "e")]
+    #[serde(borrow)]
+    pub event_type: std::borrow::Cow<'a, str>,
 
+    #[serde(rename = "s")]
+    #[serde(borrow)]
+    pub symbol: std::borrow::Cow<'a, str>,
+
+    #[serde(rename = "b")]
     pub bids: Vec<PriceSize>,
+
+    #[serde(rename = "a")]
     pub asks: Vec<PriceSize>,
+
+    #[serde(flatten)]
+    pub seq: OptionsDepthSeq,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OptionsDepthSeq {
+    #[serde(rename = "u")]
+    #[serde(default)]
+    pub last_update_id: u64,
+
+    #[serde(rename = "E")]
+    pub event_time_ms: u64,
+
+    #[serde(rename = "T")]
+    pub transaction_time_ms: u64,
+}
+
+impl<'a> From<OptionsDepth<'a>> for l2_book::Order<OptionsDepthSeq> {
+    fn from(val: OptionsDepth<'a>) -> Self {
+        l2_book::Order {
+            id: l2_book::Sequence(val.seq.last_update_id),
+            bids: val.bids.into_iter().map(Into::into).collect(),
+            asks: val.asks.into_iter().map(Into::into).collect(),
+            is_snapshot: true,
+            ts_ms: val.seq.transaction_time_ms,
+            o: val.seq,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OptionsExchangeInfo {
+    #[serde(rename = "optionSymbols")]
+    pub option_symbols: Vec<OptionSymbol>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OptionSymbol {
+    /// `{base}-{YYMMDD}-{strike}-{C|P}`, e.g. `BTC-240628-60000-C`
+    pub symbol: String,
+
+    pub underlying: String,
+
+    #[serde(rename = "expiryDate")]
+    pub expiry_date: u64,
+}
+
+/// Reply to a `SUBSCRIBE`, `UNSUBSCRIBE` or `LIST_SUBSCRIPTIONS` request
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum RequestReply {
+    /// Rejected request, `id` is missing when the request couldn't be parsed
+    Error { code: i64, msg: String, id: Option<u64> },
+    Result { result: serde_json::Value, id: u64 },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -92,7 +246,8 @@ impl From<PriceSize> for l2_book::PriceSize {
 
 #[cfg(test)]
 mod test {
-    use crate::binance::types::{DepthUpdate, PriceSize};
+    use crate::binance::types::{DepthSnapshot, DepthUpdate, OptionsDepth, PriceSize, SpotDepthUpdate};
+    use crate::l2_book::Order;
 
     #[test]
     fn deserialize_depth_update() {
@@ -120,4 +275,42 @@ mod test {
         assert_eq!(depth_update.bids, expected_bids);
         assert_eq!(depth_update.asks, expected_asks);
     }
+
+    #[test]
+    fn deserialize_spot_depth_update() {
+        let d = r#"{"e":"depthUpdate","E":1672515782136,"s":"BNBBTC","U":157,"u":160,"b":[["0.0024","10"]],"a":[["0.0026","100"]]}"#;
+        let depth_update: SpotDepthUpdate = serde_json::from_str(d).unwrap();
+
+        assert_eq!(depth_update.symbol, "BNBBTC");
+        assert_eq!(depth_update.seq.first_update_id, 157);
+        assert_eq!(depth_update.seq.last_update_id, 160);
+        assert_eq!(depth_update.seq.event_time_ms, 1672515782136);
+    }
+
+    #[test]
+    fn deserialize_spot_snapshot() {
+        let d = r#"{"lastUpdateId":1027024,"bids":[["4.00000000","431.00000000"]],"asks":[["4.00000200","12.00000000"]]}"#;
+        let snapshot: DepthSnapshot = serde_json::from_str(d).unwrap();
+
+        assert_eq!(snapshot.last_update_id, 1027024);
+        assert_eq!(snapshot.event_time_ms, None);
+        assert_eq!(snapshot.transaction_time_ms, None);
+        assert_eq!(snapshot.bids.len(), 1);
+    }
+
+    #[test]
+    fn deserialize_options_depth() {
+        let d = r#"{"e":"depth","E":1591695934033,"T":1591695934010,"s":"BTC-200630-9000-P","u":162,"pu":161,"b":[["0.1000","1.0000"]],"a":[["0.2000","2.0000"]]}"#;
+        let depth: OptionsDepth = serde_json::from_str(d).unwrap();
+
+        let order: Order<_> = depth.into();
+        assert!(order.is_snapshot);
+        assert_eq!(order.id.val(), 162);
+        assert_eq!(order.ts_ms, 1591695934010);
+
+        let d = r#"{"T":1589436922972,"u":37461,"bids":[["1000.000","0.9000"]],"asks":[["1100.000","0.1000"]]}"#;
+        let snapshot: DepthSnapshot = serde_json::from_str(d).unwrap();
+        assert_eq!(snapshot.last_update_id, 37461);
+        assert_eq!(snapshot.event_time_ms, None);
+    }
 }
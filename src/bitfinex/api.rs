@@ -0,0 +1,29 @@
+pub const WS_URL: &str = "wss://api-pub.bitfinex.com/ws/2";
+
+/// `conf` flag enabling `cs` checksum messages on every book channel
+pub const CHECKSUM_FLAG: u64 = 131072;
+
+/// Subscribe request for the raw (`R0`) book of a symbol, e.g. `tBTCUSD`.
+/// `len` is the number of orders per side, `1`, `25`, `100` or `250`.
+pub fn subscribe(symbol: &str, len: u32) -> String {
+    serde_json::json!({
+        "event": "subscribe",
+        "channel": "book",
+        "symbol": symbol,
+        "prec": "R0",
+        "len": len.to_string(),
+    })
+    .to_string()
+}
+
+/// Unsubscribe request, channels are addressed by the `chanId` of the `subscribed` event
+pub fn unsubscribe(chan_id: u64) -> String {
+    serde_json::json!({ "event": "unsubscribe", "chanId": chan_id })
+        .to_string()
+}
+
+/// Connection level configuration, send before subscribing to receive checksums
+pub fn conf_checksum() -> String {
+    serde_json::json!({ "event": "conf", "flags": CHECKSUM_FLAG })
+        .to_string()
+}
@@ -0,0 +1,105 @@
+use super::types::ChecksumState;
+use crate::l2_book::l3::{OrderBook, Side};
+use crate::l2_book::tokio::Book as AsyncBook;
+use crate::l2_book::{BookSequencer, Order, Sequence};
+use std::time::Duration;
+
+/// Number of orders per side covered by the checksum
+const CHECKSUM_DEPTH: usize = 25;
+
+/// Messages carry no sequence numbers, the book is out of sync once a checksum doesn't match
+struct BitfinexBookSequencer;
+
+impl BookSequencer<ChecksumState> for BitfinexBookSequencer {
+    fn is_first_event(&self, _cur_seq: Sequence, update: &Order<ChecksumState>) -> bool {
+        update.o.checksum_ok
+    }
+
+    fn is_stale(&self, _cur_seq: Sequence, update: &Order<ChecksumState>) -> bool {
+        !update.o.checksum_ok
+    }
+
+    fn is_next(&self, _cur_seq: Sequence, update: &Order<ChecksumState>) -> bool {
+        update.o.checksum_ok
+    }
+}
+
+/// Signed CRC32 of `id:amount` interleaved over the top 25 bid and ask orders, asks negative.
+/// Orders of one price are taken in queue order.
+pub fn checksum(book: &OrderBook) -> i32 {
+    let mut bids = book.orders(Side::Bid).take(CHECKSUM_DEPTH);
+    let mut asks = book.orders(Side::Ask).take(CHECKSUM_DEPTH);
+    let mut fields = Vec::with_capacity(CHECKSUM_DEPTH * 4);
+
+    loop {
+        let (bid, ask) = (bids.next(), asks.next());
+        if bid.is_none() && ask.is_none() {
+            break;
+        }
+
+        if let Some((id, _, size)) = bid {
+            fields.push(id.to_string());
+            fields.push(size.to_string());
+        }
+
+        if let Some((id, _, size)) = ask {
+            fields.push(id.to_string());
+            fields.push(format!("-{}", size));
+        }
+    }
+
+    crc32fast::hash(fields.join(":").as_bytes()) as i32
+}
+
+pub struct Book;
+
+impl Book {
+    /// Streamed book fed through a [`RawBook`](super::RawBook).
+    /// Resubscribe on [`snapshot_requested`](crate::l2_book::tokio::BookWriter::snapshot_requested).
+    pub fn new_bitfinex(symbol: impl Into<String>, depth: usize, interval: Duration) -> AsyncBook<ChecksumState> {
+        AsyncBook::new_streamed(symbol.into(), BitfinexBookSequencer, depth, interval)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::checksum;
+    use crate::bitfinex::RawBook;
+    use crate::bitfinex::types::ChannelMessage;
+
+    fn msg(s: &str) -> ChannelMessage {
+        serde_json::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn verifies_checksum() {
+        let mut book = RawBook::new();
+
+        assert!(book.apply(msg("[1,[10,100.5,-2]]")).is_none());
+
+        let snap = book.apply(msg("[1,[[1,100,0.5],[2,100,1.5],[3,101,-2]]]")).unwrap();
+        assert!(snap.is_snapshot);
+        assert_eq!(snap.bids.len(), 1);
+
+        let cs = crc32fast::hash(b"1:0.5:3:-2:2:1.5") as i32;
+        assert_eq!(checksum(book.orders()), cs);
+
+        let update = book.apply(msg("[1,[1,0,1]]")).unwrap();
+        assert_eq!(update.bids[0].1, crate::l2_book::Size::from(1.5));
+
+        let ok = book.apply(msg(&format!(r#"[1,"cs",{}]"#, crc32fast::hash(b"2:1.5:3:-2") as i32))).unwrap();
+        assert!(ok.o.checksum_ok);
+
+        let bad = book.apply(msg(&format!(r#"[1,"cs",{}]"#, cs))).unwrap();
+        assert!(!bad.o.checksum_ok);
+    }
+
+    #[test]
+    fn checksum_of_inexact_amounts() {
+        let mut book = RawBook::new();
+        book.apply(msg("[1,[[1,100,0.0003],[2,101,-0.0029]]]")).unwrap();
+
+        let cs = crc32fast::hash(b"1:0.0003:2:-0.0029") as i32;
+        assert_eq!(checksum(book.orders()), cs);
+    }
+}
@@ -0,0 +1,7 @@
+pub mod api;
+pub mod book;
+pub mod raw;
+pub mod types;
+
+pub use book::Book;
+pub use raw::RawBook;
@@ -0,0 +1,85 @@
+use super::book::checksum;
+use super::types::{ChannelMessage, ChecksumState, RawOrder};
+use crate::l2_book::fsm::BookSnapshot;
+use crate::l2_book::l3::{OrderBook, Side};
+use crate::l2_book::types::FLOAT_SCALE;
+use crate::l2_book::{Order, Price, PriceSize, Sequence, Size};
+
+/// Order-by-order book of one raw book channel. Every message is turned into
+/// an order carrying the aggregated levels it changed, checksums into an empty
+/// order flagging whether the book still matches the venue.
+#[derive(Default)]
+pub struct RawBook {
+    book: OrderBook,
+    seq: u64,
+    has_snapshot: bool,
+}
+
+impl RawBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Nothing is returned until the first snapshot, nor for heartbeats
+    pub fn apply(&mut self, msg: ChannelMessage) -> Option<Order<ChecksumState>> {
+        let mut order = Order {
+            id: Sequence(self.seq + 1),
+            bids: vec![],
+            asks: vec![],
+            is_snapshot: false,
+            ts_ms: 0,
+            o: ChecksumState { checksum_ok: true },
+        };
+
+        match msg {
+            ChannelMessage::Snapshot(_, orders) => {
+                self.book.clear();
+                self.has_snapshot = true;
+
+                for o in orders {
+                    self.apply_order(o);
+                }
+
+                let snapshot = self.book.snapshot(usize::MAX, 0);
+                order.bids = snapshot.bids.into_iter().map(|(p, s)| PriceSize(p, s)).collect();
+                order.asks = snapshot.asks.into_iter().map(|(p, s)| PriceSize(p, s)).collect();
+                order.is_snapshot = true;
+            }
+            ChannelMessage::Update(_, o) if self.has_snapshot => {
+                for (side, pxsz) in self.apply_order(o) {
+                    match side {
+                        Side::Bid => order.bids.push(pxsz),
+                        Side::Ask => order.asks.push(pxsz),
+                    }
+                }
+            }
+            ChannelMessage::Checksum(_, _, cs) if self.has_snapshot => {
+                order.o.checksum_ok = checksum(&self.book) == cs;
+            }
+            _ => return None,
+        }
+
+        self.seq += 1;
+        Some(order)
+    }
+
+    /// Individual orders behind the aggregated levels
+    pub fn orders(&self) -> &OrderBook {
+        &self.book
+    }
+
+    pub fn snapshot(&self, depth: usize) -> BookSnapshot {
+        self.book.snapshot(depth, 0)
+    }
+
+    fn apply_order(&mut self, RawOrder(id, price, amount): RawOrder) -> Vec<(Side, PriceSize)> {
+        if price == Price(0) {
+            return self.book.remove(id).into_iter().collect();
+        }
+
+        // Rounded, the checksum covers amounts as sent and 0.0003 would scale to 0.0002999999
+        let side = if amount < 0.0 { Side::Ask } else { Side::Bid };
+        let size = Size((amount.abs() * FLOAT_SCALE).round() as u64);
+        self.book.upsert(id, side, price, size)
+    }
+}
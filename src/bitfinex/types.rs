@@ -0,0 +1,76 @@
+use crate::l2_book;
+use serde::Deserialize;
+use serde::de::IgnoredAny;
+
+/// Message on a book channel, `[chanId, ...]`
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum ChannelMessage {
+    /// `[chanId, "cs", checksum]`
+    Checksum(u64, IgnoredAny, i32),
+    Snapshot(u64, Vec<RawOrder>),
+    Update(u64, RawOrder),
+    /// `[chanId, "hb"]`
+    Heartbeat(u64, IgnoredAny),
+}
+
+impl ChannelMessage {
+    pub fn chan_id(&self) -> u64 {
+        match *self {
+            ChannelMessage::Checksum(id, ..)
+            | ChannelMessage::Snapshot(id, _)
+            | ChannelMessage::Update(id, _)
+            | ChannelMessage::Heartbeat(id, _) => id,
+        }
+    }
+}
+
+/// `[order_id, price, amount]`, a zero price deletes the order and a negative amount is an ask
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct RawOrder(pub u64, pub l2_book::Price, pub f64);
+
+/// Event objects such as `subscribed`, `unsubscribed`, `conf` and `error`
+#[derive(Deserialize, Debug)]
+pub struct Event<'a> {
+    #[serde(borrow)]
+    pub event: std::borrow::Cow<'a, str>,
+
+    #[serde(rename = "chanId")]
+    pub chan_id: Option<u64>,
+
+    #[serde(borrow)]
+    pub symbol: Option<std::borrow::Cow<'a, str>>,
+
+    #[serde(borrow)]
+    pub msg: Option<std::borrow::Cow<'a, str>>,
+}
+
+/// Result of the latest checksum comparison of the [`RawBook`](super::RawBook)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChecksumState {
+    pub checksum_ok: bool,
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ChannelMessage, RawOrder};
+    use crate::l2_book::Price;
+
+    #[test]
+    fn deserialize_messages() {
+        let msg: ChannelMessage = serde_json::from_str("[17082,[[103245958790,9250,0.2],[103245961547,9251,-0.5]]]").unwrap();
+        let ChannelMessage::Snapshot(17082, orders) = msg else {
+            panic!("expected snapshot");
+        };
+        assert_eq!(orders[1], RawOrder(103245961547, Price::from(9251.0), -0.5));
+
+        let msg: ChannelMessage = serde_json::from_str("[17082,[103245958790,0,1]]").unwrap();
+        assert!(matches!(msg, ChannelMessage::Update(17082, RawOrder(103245958790, Price(0), _))));
+
+        let msg: ChannelMessage = serde_json::from_str(r#"[17082,"cs",-1262354932]"#).unwrap();
+        assert!(matches!(msg, ChannelMessage::Checksum(17082, _, -1262354932)));
+
+        let msg: ChannelMessage = serde_json::from_str(r#"[17082,"hb"]"#).unwrap();
+        assert!(matches!(msg, ChannelMessage::Heartbeat(17082, _)));
+    }
+}
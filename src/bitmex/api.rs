@@ -0,0 +1,15 @@
+pub const WS_URL: &str = "wss://ws.bitmex.com/realtime";
+
+/// Subscribe request for the `orderBookL2` table of a symbol, e.g. `XBTUSD`
+pub fn subscribe(symbol: &str) -> String {
+    request("subscribe", symbol)
+}
+
+pub fn unsubscribe(symbol: &str) -> String {
+    request("unsubscribe", symbol)
+}
+
+fn request(op: &str, symbol: &str) -> String {
+    serde_json::json!({ "op": op, "args": [format!("orderBookL2:{}", symbol)] })
+        .to_string()
+}
@@ -0,0 +1,31 @@
+use super::types::IdLookup;
+use crate::l2_book::tokio::Book as AsyncBook;
+use crate::l2_book::{BookSequencer, Order, Sequence};
+use std::time::Duration;
+
+/// Messages carry no sequence numbers, the book is out of sync once a level id can't be resolved
+struct BitmexBookSequencer;
+
+impl BookSequencer<IdLookup> for BitmexBookSequencer {
+    fn is_first_event(&self, _cur_seq: Sequence, update: &Order<IdLookup>) -> bool {
+        update.o.unknown_ids == 0
+    }
+
+    fn is_stale(&self, _cur_seq: Sequence, update: &Order<IdLookup>) -> bool {
+        update.o.unknown_ids > 0
+    }
+
+    fn is_next(&self, _cur_seq: Sequence, update: &Order<IdLookup>) -> bool {
+        update.o.unknown_ids == 0
+    }
+}
+
+pub struct Book;
+
+impl Book {
+    /// Streamed book fed through a [`LevelIndex`](super::LevelIndex), a `partial` resets it.
+    /// Resubscribe on [`snapshot_requested`](crate::l2_book::tokio::BookWriter::snapshot_requested).
+    pub fn new_bitmex(symbol: impl Into<String>, depth: usize, interval: Duration) -> AsyncBook<IdLookup> {
+        AsyncBook::new_streamed(symbol.into(), BitmexBookSequencer, depth, interval)
+    }
+}
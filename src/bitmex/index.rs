@@ -0,0 +1,146 @@
+use super::types::{Action, IdLookup, L2Row, Side, TableMessage};
+use crate::l2_book::types::{ZERO_SIZE, parse_rfc3339_ms};
+use crate::l2_book::{Order, Price, PriceSize, Sequence};
+use std::collections::HashMap;
+
+/// Keeps the `id` → price of every level of one symbol, so id-addressed
+/// actions can be turned into price-keyed orders.
+pub struct LevelIndex {
+    symbol: String,
+    prices: HashMap<u64, Price>,
+    seq: u64,
+    has_partial: bool,
+}
+
+impl LevelIndex {
+    pub fn new(symbol: impl Into<String>) -> Self {
+        Self {
+            symbol: symbol.into(),
+            prices: HashMap::new(),
+            seq: 0,
+            has_partial: false,
+        }
+    }
+
+    /// Resolves a table message into an order, rows of other symbols are skipped.
+    /// Nothing is returned until the first `partial`, nor for a `partial` of another symbol.
+    pub fn apply(&mut self, msg: TableMessage<'_>) -> Option<Order<IdLookup>> {
+        if msg.action == Action::Partial {
+            let filtered = msg.filter.as_ref().and_then(|f| f.symbol.as_deref()) == Some(self.symbol.as_str());
+            if !filtered && !msg.data.iter().any(|row| row.symbol == self.symbol) {
+                return None;
+            }
+
+            self.prices.clear();
+            self.has_partial = true;
+        }
+
+        if !self.has_partial {
+            return None;
+        }
+
+        self.seq += 1;
+
+        let mut order = Order {
+            id: Sequence(self.seq),
+            bids: vec![],
+            asks: vec![],
+            is_snapshot: msg.action == Action::Partial,
+            ts_ms: 0,
+            o: IdLookup { unknown_ids: 0 },
+        };
+
+        for row in msg.data.iter() {
+            if row.symbol != self.symbol {
+                continue;
+            }
+
+            let Some(pxsz) = self.resolve(msg.action, row) else {
+                order.o.unknown_ids += 1;
+                continue;
+            };
+
+            match row.side {
+                Side::Buy => order.bids.push(pxsz),
+                Side::Sell => order.asks.push(pxsz),
+            }
+
+            let ts_ms = row.timestamp.as_deref().and_then(parse_rfc3339_ms).unwrap_or_default();
+            order.ts_ms = order.ts_ms.max(ts_ms);
+        }
+
+        Some(order)
+    }
+
+    fn resolve(&mut self, action: Action, row: &L2Row<'_>) -> Option<PriceSize> {
+        match action {
+            Action::Partial | Action::Insert => {
+                let price = row.price?;
+                self.prices.insert(row.id, price);
+                Some(PriceSize(price, row.size?))
+            }
+            Action::Update => {
+                let price = row.price.or_else(|| self.prices.get(&row.id).copied())?;
+                Some(PriceSize(price, row.size?))
+            }
+            Action::Delete => {
+                let price = self.prices.remove(&row.id).or(row.price)?;
+                Some(PriceSize(price, ZERO_SIZE))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::LevelIndex;
+    use crate::bitmex::types::{IdLookup, TableMessage};
+    use crate::l2_book::{Order, Price, PriceSize, Size};
+
+    fn apply(index: &mut LevelIndex, d: &str) -> Option<Order<IdLookup>> {
+        index.apply(serde_json::from_str::<TableMessage>(d).unwrap())
+    }
+
+    #[test]
+    fn resolves_ids() {
+        let mut index = LevelIndex::new("XBTUSD");
+
+        // Updates before the partial are ignored
+        let update = r#"{"table":"orderBookL2","action":"update","data":[{"symbol":"XBTUSD","id":1,"side":"Sell","size":5}]}"#;
+        assert!(apply(&mut index, update).is_none());
+
+        let partial = r#"{"table":"orderBookL2","action":"partial","data":[{"symbol":"XBTUSD","id":1,"side":"Sell","size":100,"price":6132.5},{"symbol":"XBTUSD","id":2,"side":"Buy","size":50,"price":6132.0},{"symbol":"ETHUSD","id":3,"side":"Buy","size":1,"price":3000}]}"#;
+        let order = apply(&mut index, partial).unwrap();
+        assert!(order.is_snapshot);
+        assert_eq!(order.bids.len(), 1);
+
+        let order = apply(&mut index, update).unwrap();
+        assert_eq!(order.asks, vec![PriceSize(Price::from(6132.5), Size::from(5.0))]);
+
+        let delete = r#"{"table":"orderBookL2","action":"delete","data":[{"symbol":"XBTUSD","id":2,"side":"Buy"},{"symbol":"XBTUSD","id":9,"side":"Buy"}]}"#;
+        let order = apply(&mut index, delete).unwrap();
+        assert_eq!(order.bids, vec![PriceSize(Price::from(6132.0), Size(0))]);
+        assert_eq!(order.o.unknown_ids, 1);
+    }
+
+    #[test]
+    fn skips_foreign_partial() {
+        let mut index = LevelIndex::new("XBTUSD");
+
+        let partial = r#"{"table":"orderBookL2","action":"partial","filter":{"symbol":"XBTUSD"},"data":[{"symbol":"XBTUSD","id":1,"side":"Sell","size":100,"price":6132.5}]}"#;
+        assert!(apply(&mut index, partial).unwrap().is_snapshot);
+
+        let foreign = r#"{"table":"orderBookL2","action":"partial","filter":{"symbol":"ETHUSD"},"data":[{"symbol":"ETHUSD","id":3,"side":"Buy","size":1,"price":3000}]}"#;
+        assert!(apply(&mut index, foreign).is_none());
+
+        // Prices of this symbol survive the foreign partial
+        let delete = r#"{"table":"orderBookL2","action":"delete","data":[{"symbol":"XBTUSD","id":1,"side":"Sell"}]}"#;
+        let order = apply(&mut index, delete).unwrap();
+        assert_eq!(order.asks, vec![PriceSize(Price::from(6132.5), Size(0))]);
+        assert_eq!(order.o.unknown_ids, 0);
+
+        // An empty book of this symbol is still a snapshot
+        let empty = r#"{"table":"orderBookL2","action":"partial","filter":{"symbol":"XBTUSD"},"data":[]}"#;
+        assert!(apply(&mut index, empty).unwrap().is_snapshot);
+    }
+}
@@ -0,0 +1,7 @@
+pub mod api;
+pub mod book;
+pub mod index;
+pub mod types;
+
+pub use book::Book;
+pub use index::LevelIndex;
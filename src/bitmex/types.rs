@@ -0,0 +1,89 @@
+use crate::l2_book;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Partial,
+    Insert,
+    Update,
+    Delete,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct TableMessage<'a> {
+    #[serde(borrow)]
+    pub table: std::borrow::Cow<'a, str>,
+
+    pub action: Action,
+
+    #[serde(borrow)]
+    pub data: Vec<L2Row<'a>>,
+
+    /// Sent with `partial`, names the symbol even when it has no rows
+    #[serde(borrow, default)]
+    pub filter: Option<Filter<'a>>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct Filter<'a> {
+    #[serde(borrow)]
+    pub symbol: Option<std::borrow::Cow<'a, str>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+/// Level keyed by `id`, `update` and `delete` rows may omit the price
+#[derive(Deserialize, Debug)]
+pub struct L2Row<'a> {
+    #[serde(borrow)]
+    pub symbol: std::borrow::Cow<'a, str>,
+
+    pub id: u64,
+
+    pub side: Side,
+
+    pub size: Option<l2_book::Size>,
+
+    pub price: Option<l2_book::Price>,
+
+    #[serde(borrow)]
+    pub timestamp: Option<std::borrow::Cow<'a, str>>,
+}
+
+/// Result of resolving level ids against the [`LevelIndex`](super::LevelIndex)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdLookup {
+    /// Rows whose `id` was never inserted, the book is out of sync
+    pub unknown_ids: usize,
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Action, Side, TableMessage};
+    use crate::l2_book::{Price, Size};
+
+    #[test]
+    fn deserialize_update() {
+        let d = r#"{"table":"orderBookL2","action":"update","data":[{"symbol":"XBTUSD","id":8799386750,"side":"Sell","size":200,"timestamp":"2024-01-01T00:00:00.000Z"}]}"#;
+        let msg: TableMessage = serde_json::from_str(d).unwrap();
+
+        assert_eq!(msg.action, Action::Update);
+        assert_eq!(msg.data[0].side, Side::Sell);
+        assert_eq!(msg.data[0].size, Some(Size::from(200.0)));
+        assert_eq!(msg.data[0].price, None);
+    }
+
+    #[test]
+    fn deserialize_partial() {
+        let d = r#"{"table":"orderBookL2","action":"partial","keys":["symbol","id","side"],"data":[{"symbol":"XBTUSD","id":8799386750,"side":"Sell","size":100,"price":6132.5,"timestamp":"2024-01-01T00:00:00.000Z"}]}"#;
+        let msg: TableMessage = serde_json::from_str(d).unwrap();
+
+        assert_eq!(msg.action, Action::Partial);
+        assert_eq!(msg.data[0].price, Some(Price::from(6132.5)));
+    }
+}
@@ -0,0 +1,55 @@
+use super::types::{OrderBookL3Snapshot, OrderBookSnapshot};
+
+/// Bitstamp API
+pub struct Bitstamp;
+
+impl Bitstamp {
+    pub fn rest_url(&self) -> &str {
+        "https://www.bitstamp.net/api/v2"
+    }
+
+    pub fn ws_url(&self) -> &str {
+        "wss://ws.bitstamp.net"
+    }
+
+    /// Full book aggregated by price
+    pub async fn get_order_book(&self, pair: &str) -> Result<OrderBookSnapshot, reqwest::Error> {
+        self.order_book(pair, "1").await
+    }
+
+    /// Full book with every order and its id
+    pub async fn get_order_book_l3(&self, pair: &str) -> Result<OrderBookL3Snapshot, reqwest::Error> {
+        self.order_book(pair, "2").await
+    }
+
+    async fn order_book<T: serde::de::DeserializeOwned>(&self, pair: &str, group: &str) -> Result<T, reqwest::Error> {
+        let client = reqwest::Client::new();
+        let url = format!("{}/order_book/{}/", self.rest_url(), pair);
+
+        let resp = client.get(&url).query(&[("group", group)]).send().await?;
+        resp.json::<T>().await
+    }
+}
+
+/// Price level changes of a pair, e.g. `btcusd`
+pub fn diff_channel(pair: &str) -> String {
+    format!("diff_order_book_{}", pair)
+}
+
+/// Live full order book, every order created, changed or deleted
+pub fn live_orders_channel(pair: &str) -> String {
+    format!("live_orders_{}", pair)
+}
+
+pub fn subscribe(channel: &str) -> String {
+    request("bts:subscribe", channel)
+}
+
+pub fn unsubscribe(channel: &str) -> String {
+    request("bts:unsubscribe", channel)
+}
+
+fn request(event: &str, channel: &str) -> String {
+    serde_json::json!({ "event": event, "data": { "channel": channel } })
+        .to_string()
+}
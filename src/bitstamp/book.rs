@@ -0,0 +1,107 @@
+use super::api::Bitstamp;
+use super::types::Microtimestamp;
+use crate::l2_book::tokio::{Book as AsyncBook, SnapshotFetcher};
+use crate::l2_book::{BookSequencer, Order, Sequence};
+use std::time::Duration;
+
+/// Diffs are only ordered by `microtimestamp`, those not newer than the snapshot are already in it.
+/// Gaps can't be detected.
+struct DiffBookSequencer;
+
+impl BookSequencer<Microtimestamp> for DiffBookSequencer {
+    fn is_first_event(&self, cur_seq: Sequence, update: &Order<Microtimestamp>) -> bool {
+        update.o.0 > cur_seq.val()
+    }
+
+    fn is_stale(&self, _cur_seq: Sequence, _update: &Order<Microtimestamp>) -> bool {
+        false
+    }
+
+    fn is_next(&self, cur_seq: Sequence, update: &Order<Microtimestamp>) -> bool {
+        update.o.0 > cur_seq.val()
+    }
+}
+
+/// Orders come out of a [`LiveOrderBook`](super::LiveOrderBook) already filtered against
+/// the snapshot, several orders can share a `microtimestamp`.
+/// Gaps can't be detected and never trigger a resync, [`LiveOrderBook::reset`](super::LiveOrderBook::reset)
+/// has to be called when events may have been lost.
+struct LiveOrdersSequencer;
+
+impl BookSequencer<Microtimestamp> for LiveOrdersSequencer {
+    fn is_first_event(&self, cur_seq: Sequence, update: &Order<Microtimestamp>) -> bool {
+        update.o.0 >= cur_seq.val()
+    }
+
+    fn is_stale(&self, _cur_seq: Sequence, _update: &Order<Microtimestamp>) -> bool {
+        false
+    }
+
+    fn is_next(&self, cur_seq: Sequence, update: &Order<Microtimestamp>) -> bool {
+        update.o.0 >= cur_seq.val()
+    }
+}
+
+pub struct BitstampSnapshotFetcher {
+    api: Bitstamp,
+}
+
+impl SnapshotFetcher<Microtimestamp> for BitstampSnapshotFetcher {
+    type Error = reqwest::Error;
+
+    async fn fetch_snapshot(&self, pair: &str) -> Result<Order<Microtimestamp>, Self::Error> {
+        Ok(self.api.get_order_book(pair).await?.into())
+    }
+}
+
+pub struct Book;
+
+impl Book {
+    /// Book of a `diff_order_book` channel
+    pub fn new_bitstamp(pair: impl Into<String>, depth: usize, interval: Duration) -> AsyncBook<Microtimestamp> {
+        AsyncBook::new(
+            pair.into(),
+            DiffBookSequencer,
+            BitstampSnapshotFetcher { api: Bitstamp },
+            depth,
+            interval,
+        )
+    }
+
+    /// Aggregated book of a `live_orders` channel fed through a [`LiveOrderBook`](super::LiveOrderBook).
+    /// Feed it [`get_order_book_l3`](super::api::Bitstamp::get_order_book_l3) after subscribing
+    /// and on [`snapshot_requested`](crate::l2_book::tokio::BookWriter::snapshot_requested).
+    pub fn new_bitstamp_l3(pair: impl Into<String>, depth: usize, interval: Duration) -> AsyncBook<Microtimestamp> {
+        AsyncBook::new_streamed(pair.into(), LiveOrdersSequencer, depth, interval)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DiffBookSequencer;
+    use crate::bitstamp::types::Microtimestamp;
+    use crate::l2_book::{BookAction, BookFsm, Order, Price, PriceSize, Sequence, Size};
+
+    fn order(ts: u64, is_snapshot: bool, bid: (f64, f64)) -> Order<Microtimestamp> {
+        Order {
+            id: Sequence(ts),
+            bids: vec![PriceSize(Price::from(bid.0), Size::from(bid.1))],
+            asks: vec![],
+            is_snapshot,
+            ts_ms: ts / 1000,
+            o: Microtimestamp(ts),
+        }
+    }
+
+    #[test]
+    fn drops_diffs_covered_by_snapshot() {
+        let mut fsm = BookFsm::new(DiffBookSequencer);
+
+        assert_eq!(BookAction::RetrieveSnapshot, fsm.update(order(1_000, false, (100.0, 1.0))));
+        assert_eq!(BookAction::Ok, fsm.update(order(3_000, false, (100.0, 3.0))));
+
+        // Snapshot taken between the two diffs
+        assert_eq!(BookAction::Ok, fsm.update(order(2_000, true, (100.0, 2.0))));
+        assert_eq!(fsm.snapshot(1).bids, vec![(Price::from(100.0), Size::from(3.0))]);
+    }
+}
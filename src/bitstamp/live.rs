@@ -0,0 +1,163 @@
+use super::types::{LiveOrder, LiveOrderMessage, Microtimestamp, OrderBookL3Snapshot, OrderEvent};
+use crate::l2_book::fsm::BookSnapshot;
+use crate::l2_book::l3::{OrderBook, Side};
+use crate::l2_book::{Order, PriceSize, Sequence};
+
+/// Order-by-order book fed by a `live_orders` channel and a `group=2` REST snapshot.
+/// Events are buffered until the snapshot, then those not newer than it are dropped.
+/// Every applied event is turned into an order carrying the aggregated levels it changed.
+///
+/// Missed events can't be detected, call [`reset`](Self::reset) and fetch a new snapshot on
+/// [`snapshot_requested`](crate::l2_book::tokio::BookWriter::snapshot_requested) and on
+/// [`FeedError::Disconnected`](crate::feed::FeedError::Disconnected).
+#[derive(Default)]
+pub struct LiveOrderBook {
+    book: OrderBook,
+    snapshot_ts: Option<u64>,
+    buffer: Vec<LiveOrderMessage<'static>>,
+}
+
+impl LiveOrderBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the book, returns the snapshot order followed by the buffered events it didn't cover
+    pub fn apply_snapshot(&mut self, snapshot: OrderBookL3Snapshot) -> Vec<Order<Microtimestamp>> {
+        self.book.clear();
+
+        for o in snapshot.bids.iter() {
+            self.book.upsert(o.2, Side::Bid, o.0, o.1);
+        }
+
+        for o in snapshot.asks.iter() {
+            self.book.upsert(o.2, Side::Ask, o.0, o.1);
+        }
+
+        let ts = Microtimestamp(snapshot.microtimestamp);
+        let levels = self.book.snapshot(usize::MAX, ts.ms());
+        let mut orders = vec![Order {
+            id: Sequence(ts.0),
+            bids: levels.bids.into_iter().map(|(p, s)| PriceSize(p, s)).collect(),
+            asks: levels.asks.into_iter().map(|(p, s)| PriceSize(p, s)).collect(),
+            is_snapshot: true,
+            ts_ms: ts.ms(),
+            o: ts,
+        }];
+
+        self.snapshot_ts = Some(ts.0);
+        for msg in std::mem::take(&mut self.buffer) {
+            orders.extend(self.apply(msg));
+        }
+
+        orders
+    }
+
+    /// Drops the book, events are buffered again until the next [`apply_snapshot`](Self::apply_snapshot)
+    pub fn reset(&mut self) {
+        self.book.clear();
+        self.snapshot_ts = None;
+        self.buffer.clear();
+    }
+
+    /// `None` while waiting for the snapshot or when the event is already part of it
+    pub fn apply(&mut self, msg: LiveOrderMessage<'_>) -> Option<Order<Microtimestamp>> {
+        let Some(snapshot_ts) = self.snapshot_ts else {
+            self.buffer.push(LiveOrderMessage {
+                event: msg.event,
+                channel: msg.channel.into_owned().into(),
+                data: msg.data,
+            });
+            return None;
+        };
+
+        let LiveOrder {
+            id,
+            order_type,
+            microtimestamp,
+            amount,
+            price,
+        } = msg.data;
+
+        if microtimestamp <= snapshot_ts {
+            return None;
+        }
+
+        let side = if order_type == 0 { Side::Bid } else { Side::Ask };
+        let changes = match msg.event {
+            OrderEvent::OrderCreated | OrderEvent::OrderChanged => self.book.upsert(id, side, price, amount),
+            OrderEvent::OrderDeleted => self.book.remove(id).into_iter().collect(),
+        };
+
+        let ts = Microtimestamp(microtimestamp);
+        let mut order = Order {
+            id: Sequence(ts.0),
+            bids: vec![],
+            asks: vec![],
+            is_snapshot: false,
+            ts_ms: ts.ms(),
+            o: ts,
+        };
+
+        for (side, pxsz) in changes {
+            match side {
+                Side::Bid => order.bids.push(pxsz),
+                Side::Ask => order.asks.push(pxsz),
+            }
+        }
+
+        Some(order)
+    }
+
+    /// Individual orders behind the aggregated levels
+    pub fn orders(&self) -> &OrderBook {
+        &self.book
+    }
+
+    pub fn snapshot(&self, depth: usize) -> BookSnapshot {
+        self.book.snapshot(depth, 0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::LiveOrderBook;
+    use crate::bitstamp::types::{LiveOrderMessage, OrderBookL3Snapshot};
+    use crate::l2_book::{Price, Size};
+
+    fn created(id: u64, ts: u64, price: u64) -> LiveOrderMessage<'static> {
+        let d = format!(
+            r#"{{"data":{{"id":{id},"order_type":0,"microtimestamp":"{ts}","amount":1,"price":{price}}},"channel":"live_orders_btcusd","event":"order_created"}}"#
+        );
+        let msg: LiveOrderMessage = serde_json::from_str(&d).unwrap();
+        LiveOrderMessage {
+            event: msg.event,
+            channel: msg.channel.into_owned().into(),
+            data: msg.data,
+        }
+    }
+
+    fn snapshot(ts: u64) -> OrderBookL3Snapshot {
+        let d = format!(r#"{{"microtimestamp":"{ts}","bids":[["100","2","1"]],"asks":[]}}"#);
+        serde_json::from_str(&d).unwrap()
+    }
+
+    #[test]
+    fn reset_buffers_until_snapshot() {
+        let mut book = LiveOrderBook::new();
+        assert_eq!(book.apply_snapshot(snapshot(1_000)).len(), 1);
+        assert!(book.apply(created(2, 2_000, 101)).is_some());
+
+        book.reset();
+        assert_eq!(book.orders().len(), 0);
+
+        // Buffered, then replayed on top of the next snapshot
+        assert!(book.apply(created(3, 4_000, 102)).is_none());
+        let orders = book.apply_snapshot(snapshot(3_000));
+        assert_eq!(orders.len(), 2);
+        assert_eq!(
+            book.snapshot(10).bids,
+            vec![(Price::from(102.0), Size::from(1.0)), (Price::from(100.0), Size::from(2.0))]
+        );
+    }
+}
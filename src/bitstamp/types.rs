@@ -0,0 +1,158 @@
+use crate::l2_book;
+use crate::l2_book::types::u64_from_str;
+use serde::{Deserialize, Serialize};
+
+/// Microseconds since epoch, the only ordering Bitstamp books carry
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Microtimestamp(pub u64);
+
+impl Microtimestamp {
+    pub fn ms(&self) -> u64 {
+        self.0 / 1000
+    }
+}
+
+/// Message of a `diff_order_book` channel
+#[derive(Deserialize, Debug)]
+pub struct DiffMessage<'a> {
+    #[serde(borrow)]
+    pub event: std::borrow::Cow<'a, str>,
+
+    #[serde(borrow)]
+    pub channel: std::borrow::Cow<'a, str>,
+
+    pub data: DiffData,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DiffData {
+    #[serde(with = "u64_from_str")]
+    pub microtimestamp: u64,
+
+    pub bids: Vec<l2_book::PriceSize>,
+
+    pub asks: Vec<l2_book::PriceSize>,
+}
+
+/// `GET /api/v2/order_book/{pair}/?group=1`
+#[derive(Deserialize, Debug)]
+pub struct OrderBookSnapshot {
+    #[serde(with = "u64_from_str")]
+    pub microtimestamp: u64,
+
+    pub bids: Vec<l2_book::PriceSize>,
+
+    pub asks: Vec<l2_book::PriceSize>,
+}
+
+/// `GET /api/v2/order_book/{pair}/?group=2`
+#[derive(Deserialize, Debug)]
+pub struct OrderBookL3Snapshot {
+    #[serde(with = "u64_from_str")]
+    pub microtimestamp: u64,
+
+    pub bids: Vec<RestingOrder>,
+
+    pub asks: Vec<RestingOrder>,
+}
+
+/// `[price, amount, order id]`
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct RestingOrder(pub l2_book::Price, pub l2_book::Size, #[serde(with = "u64_from_str")] pub u64);
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderEvent {
+    OrderCreated,
+    OrderChanged,
+    OrderDeleted,
+}
+
+/// Order event of a `live_orders` channel, `bts:*` control events don't parse as one
+#[derive(Deserialize, Debug)]
+pub struct LiveOrderMessage<'a> {
+    pub event: OrderEvent,
+
+    #[serde(borrow)]
+    pub channel: std::borrow::Cow<'a, str>,
+
+    pub data: LiveOrder,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct LiveOrder {
+    pub id: u64,
+
+    /// `0` buy, `1` sell
+    pub order_type: u8,
+
+    #[serde(with = "u64_from_str")]
+    pub microtimestamp: u64,
+
+    /// Amount left on the book
+    pub amount: l2_book::Size,
+
+    pub price: l2_book::Price,
+}
+
+impl From<DiffMessage<'_>> for l2_book::Order<Microtimestamp> {
+    fn from(val: DiffMessage<'_>) -> Self {
+        let ts = Microtimestamp(val.data.microtimestamp);
+
+        l2_book::Order {
+            id: l2_book::Sequence(ts.0),
+            bids: val.data.bids,
+            asks: val.data.asks,
+            is_snapshot: false,
+            ts_ms: ts.ms(),
+            o: ts,
+        }
+    }
+}
+
+impl From<OrderBookSnapshot> for l2_book::Order<Microtimestamp> {
+    fn from(val: OrderBookSnapshot) -> Self {
+        let ts = Microtimestamp(val.microtimestamp);
+
+        l2_book::Order {
+            id: l2_book::Sequence(ts.0),
+            bids: val.bids,
+            asks: val.asks,
+            is_snapshot: true,
+            ts_ms: ts.ms(),
+            o: ts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DiffMessage, LiveOrderMessage, OrderBookL3Snapshot, OrderEvent};
+    use crate::l2_book::{Order, Price, PriceSize, Size};
+
+    #[test]
+    fn deserialize_diff() {
+        let d = r#"{"data":{"timestamp":"1700000000","microtimestamp":"1700000000123456","bids":[["37000.5","0.25"]],"asks":[]},"channel":"diff_order_book_btcusd","event":"data"}"#;
+        let msg: DiffMessage = serde_json::from_str(d).unwrap();
+
+        let order: Order<_> = msg.into();
+        assert_eq!(order.id.val(), 1700000000123456);
+        assert_eq!(order.ts_ms, 1700000000123);
+        assert_eq!(order.bids[0], PriceSize(Price::from(37000.5), Size::from(0.25)));
+    }
+
+    #[test]
+    fn deserialize_live_orders() {
+        let d = r#"{"data":{"id":1660000000000001,"id_str":"1660000000000001","order_type":1,"datetime":"1700000000","microtimestamp":"1700000000123456","amount":0.5,"amount_str":"0.50000000","price":37001,"price_str":"37001"},"channel":"live_orders_btcusd","event":"order_created"}"#;
+        let msg: LiveOrderMessage = serde_json::from_str(d).unwrap();
+        assert_eq!(msg.event, OrderEvent::OrderCreated);
+        assert_eq!(msg.data.price, Price::from(37001.0));
+
+        let d = r#"{"event":"bts:subscription_succeeded","channel":"live_orders_btcusd","data":{}}"#;
+        assert!(serde_json::from_str::<LiveOrderMessage>(d).is_err());
+
+        let d = r#"{"timestamp":"1700000000","microtimestamp":"1700000000000000","bids":[["37000","1.5","1660000000000000"]],"asks":[]}"#;
+        let msg: OrderBookL3Snapshot = serde_json::from_str(d).unwrap();
+        assert_eq!(msg.bids[0].2, 1660000000000000);
+    }
+}
@@ -0,0 +1,52 @@
+/// Bybit v5 public market categories, each one has its own stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Spot,
+    Linear,
+    Inverse,
+    Option,
+}
+
+impl Category {
+    pub fn ws_url(&self) -> &str {
+        match self {
+            Category::Spot => "wss://stream.bybit.com/v5/public/spot",
+            Category::Linear => "wss://stream.bybit.com/v5/public/linear",
+            Category::Inverse => "wss://stream.bybit.com/v5/public/inverse",
+            Category::Option => "wss://stream.bybit.com/v5/public/option",
+        }
+    }
+}
+
+/// Orderbook stream of one category at a given depth, see [`Venue`](crate::venue::Venue)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bybit {
+    pub category: Category,
+    pub depth: usize,
+}
+
+impl Default for Bybit {
+    fn default() -> Self {
+        Self {
+            category: Category::Linear,
+            depth: 50,
+        }
+    }
+}
+
+/// Orderbook topic, e.g. `orderbook.50.BTCUSDT`
+pub fn orderbook_topic(depth: usize, symbol: &str) -> String {
+    format!("orderbook.{}.{}", depth, symbol)
+}
+
+pub fn subscribe(topics: &[&str]) -> String {
+    request("subscribe", topics)
+}
+
+pub fn unsubscribe(topics: &[&str]) -> String {
+    request("unsubscribe", topics)
+}
+
+fn request(op: &str, topics: &[&str]) -> String {
+    serde_json::json!({ "op": op, "args": topics }).to_string()
+}
@@ -0,0 +1,67 @@
+use super::api::{Bybit, orderbook_topic, subscribe, unsubscribe};
+use super::decode::OrderbookDecoder;
+use super::types::UpdateSeq;
+use crate::l2_book::tokio::{Book as AsyncBook, NoSnapshotFetcher};
+use crate::l2_book::{BookSequencer, Order, Sequence};
+use crate::venue::Venue;
+use crate::ws::Message;
+use std::time::Duration;
+
+/// Bybit does not document `u` as gapless, deltas are only required to move forward.
+/// A lost delta goes unnoticed here, the feed resets the book on frames it can't decode.
+/// Snapshots come from the stream on subscribe and whenever Bybit resets the book.
+pub struct BybitBookSequencer;
+
+impl BookSequencer<UpdateSeq> for BybitBookSequencer {
+    fn is_first_event(&self, cur_seq: Sequence, update: &Order<UpdateSeq>) -> bool {
+        cur_seq.val() < update.o.update_id
+    }
+
+    fn is_stale(&self, _cur_seq: Sequence, _update: &Order<UpdateSeq>) -> bool {
+        false
+    }
+
+    fn is_next(&self, cur_seq: Sequence, update: &Order<UpdateSeq>) -> bool {
+        cur_seq.val() < update.o.update_id
+    }
+}
+
+impl Venue for Bybit {
+    type Seq = UpdateSeq;
+    type Sequencer = BybitBookSequencer;
+    type Fetcher = NoSnapshotFetcher;
+    type Decoder = OrderbookDecoder;
+
+    fn stream_url(&self, _symbol: &str) -> String {
+        self.category.ws_url().to_string()
+    }
+
+    fn subscribe(&self, symbol: &str) -> Vec<Message> {
+        vec![subscribe(&[&orderbook_topic(self.depth, symbol)]).into()]
+    }
+
+    fn unsubscribe(&self, symbol: &str) -> Vec<Message> {
+        vec![unsubscribe(&[&orderbook_topic(self.depth, symbol)]).into()]
+    }
+
+    fn decoder(&self) -> Self::Decoder {
+        OrderbookDecoder
+    }
+
+    fn sequencer(&self) -> Self::Sequencer {
+        BybitBookSequencer
+    }
+
+    fn fetcher(&self) -> Option<Self::Fetcher> {
+        None
+    }
+}
+
+pub struct Book;
+
+impl Book {
+    /// Streamed book, resubscribe to the topic on [`snapshot_requested`](crate::l2_book::tokio::BookWriter::snapshot_requested)
+    pub fn new_bybit(symbol: impl Into<String>, depth: usize, interval: Duration) -> AsyncBook<UpdateSeq> {
+        AsyncBook::new_streamed(symbol.into(), BybitBookSequencer, depth, interval)
+    }
+}
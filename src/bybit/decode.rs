@@ -0,0 +1,47 @@
+use super::types::{OrderbookMessage, UpdateSeq};
+use crate::feed::{Control, Decoder, Event};
+use serde::Deserialize;
+use serde::de::IgnoredAny;
+
+/// `orderbook` topic messages, op replies and pongs are control messages
+#[derive(Debug, Default)]
+pub struct OrderbookDecoder;
+
+/// Op replies and pongs have no `topic`
+#[derive(Deserialize)]
+struct Topic {
+    topic: Option<IgnoredAny>,
+}
+
+impl Decoder for OrderbookDecoder {
+    type Seq = UpdateSeq;
+
+    fn decode(&mut self, frame: &mut [u8], events: &mut Vec<Event<UpdateSeq>>) {
+        let event = match serde_json::from_slice::<Topic>(frame) {
+            Ok(Topic { topic: None }) => Ok(Event::Control(Control::Message(frame.to_vec()))),
+            Ok(_) => serde_json::from_slice::<OrderbookMessage>(frame).map(|msg| Event::Book(msg.into())),
+            Err(e) => Err(e),
+        };
+
+        events.push(event.unwrap_or_else(Event::decode_error));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::OrderbookDecoder;
+    use crate::feed::{Control, Decoder, Event};
+
+    #[test]
+    fn decode_skips_op_replies() {
+        let mut events = vec![];
+
+        let mut ack = br#"{"success":true,"ret_msg":"","conn_id":"abc","req_id":"","op":"subscribe"}"#.to_vec();
+        OrderbookDecoder.decode(&mut ack, &mut events);
+        assert!(matches!(events.pop(), Some(Event::Control(Control::Message(_)))));
+
+        let mut d = br#"{"topic":"orderbook.50.BTCUSDT","type":"snapshot","ts":1672304484978,"data":{"s":"BTCUSDT","b":[["16493.50","0.006"]],"a":[["16611.00","0.029"]],"u":18521288,"seq":7961638724},"cts":1672304484976}"#.to_vec();
+        OrderbookDecoder.decode(&mut d, &mut events);
+        assert!(matches!(events.pop(), Some(Event::Book(o)) if o.is_snapshot));
+    }
+}
@@ -0,0 +1,6 @@
+pub mod api;
+pub mod book;
+pub mod decode;
+pub mod types;
+
+pub use book::Book;
@@ -0,0 +1,94 @@
+use crate::l2_book;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageType {
+    Snapshot,
+    Delta,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct OrderbookMessage<'a> {
+    #[serde(borrow)]
+    pub topic: std::borrow::Cow<'a, str>,
+
+    #[serde(rename = "type")]
+    pub kind: MessageType,
+
+    pub ts: u64,
+
+    pub data: OrderbookData<'a>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct OrderbookData<'a> {
+    #[serde(rename = "s")]
+    #[serde(borrow)]
+    pub symbol: std::borrow::Cow<'a, str>,
+
+    #[serde(rename = "b")]
+    pub bids: Vec<l2_book::PriceSize>,
+
+    #[serde(rename = "a")]
+    pub asks: Vec<l2_book::PriceSize>,
+
+    #[serde(flatten)]
+    pub seq: UpdateSeq,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UpdateSeq {
+    #[serde(rename = "u")]
+    pub update_id: u64,
+
+    #[serde(rename = "seq")]
+    pub cross_seq: u64,
+}
+
+impl<'a> From<OrderbookMessage<'a>> for l2_book::Order<UpdateSeq> {
+    fn from(val: OrderbookMessage<'a>) -> Self {
+        let seq = val.data.seq;
+
+        l2_book::Order {
+            id: l2_book::Sequence(seq.update_id),
+            bids: val.data.bids,
+            asks: val.data.asks,
+            is_snapshot: val.kind == MessageType::Snapshot,
+            ts_ms: val.ts,
+            o: seq,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MessageType, OrderbookMessage};
+    use crate::l2_book::{Order, Price, PriceSize, Size};
+
+    #[test]
+    fn deserialize_snapshot() {
+        let d = r#"{"topic":"orderbook.50.BTCUSDT","type":"snapshot","ts":1672304484978,"data":{"s":"BTCUSDT","b":[["16493.50","0.006"],["16493.00","0.100"]],"a":[["16611.00","0.029"]],"u":18521288,"seq":7961638724},"cts":1672304484976}"#;
+        let msg: OrderbookMessage = serde_json::from_str(d).unwrap();
+
+        assert_eq!(msg.kind, MessageType::Snapshot);
+        assert_eq!(msg.data.symbol, "BTCUSDT");
+        assert_eq!(msg.data.seq.update_id, 18521288);
+        assert_eq!(msg.data.bids[0], PriceSize(Price::from(16493.5), Size::from(0.006)));
+
+        let order: Order<_> = msg.into();
+        assert!(order.is_snapshot);
+        assert_eq!(order.bids.len(), 2);
+        assert_eq!(order.asks.len(), 1);
+    }
+
+    #[test]
+    fn deserialize_delta() {
+        let d = r#"{"topic":"orderbook.50.BTCUSDT","type":"delta","ts":1687940967466,"data":{"s":"BTCUSDT","b":[["30247.20","30.028"],["30245.40","0"]],"a":[],"u":177400507,"seq":66544703342},"cts":1687940967464}"#;
+        let order: Order<_> = serde_json::from_str::<OrderbookMessage>(d).unwrap().into();
+
+        assert!(!order.is_snapshot);
+        assert_eq!(order.id.val(), 177400507);
+        assert_eq!(order.bids[1].size(), Size(0));
+    }
+}
@@ -0,0 +1,15 @@
+pub const WS_URL: &str = "wss://advanced-trade-ws.coinbase.com";
+
+/// Subscribe request for the `level2` channel
+pub fn subscribe(product_ids: &[&str]) -> String {
+    request("subscribe", product_ids)
+}
+
+pub fn unsubscribe(product_ids: &[&str]) -> String {
+    request("unsubscribe", product_ids)
+}
+
+fn request(kind: &str, product_ids: &[&str]) -> String {
+    serde_json::json!({ "type": kind, "product_ids": product_ids, "channel": "level2" })
+        .to_string()
+}
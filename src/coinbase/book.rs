@@ -0,0 +1,32 @@
+use super::types::SequenceNum;
+use crate::l2_book::tokio::Book as AsyncBook;
+use crate::l2_book::{BookSequencer, Order, Sequence};
+use std::time::Duration;
+
+/// `sequence_num` must be gapless, recovery is a fresh subscription rather than a REST snapshot
+struct CoinbaseBookSequencer;
+
+impl BookSequencer<SequenceNum> for CoinbaseBookSequencer {
+    fn is_first_event(&self, cur_seq: Sequence, update: &Order<SequenceNum>) -> bool {
+        cur_seq.val() + 1 == update.o.sequence_num
+    }
+
+    fn is_stale(&self, cur_seq: Sequence, update: &Order<SequenceNum>) -> bool {
+        cur_seq.val() + 1 < update.o.sequence_num
+    }
+
+    fn is_next(&self, cur_seq: Sequence, update: &Order<SequenceNum>) -> bool {
+        cur_seq.val() + 1 == update.o.sequence_num
+    }
+}
+
+pub struct Book;
+
+impl Book {
+    /// Streamed book, every message of the connection must be written to it through
+    /// [`Message::to_order`](super::types::Message::to_order). Resubscribe on
+    /// [`snapshot_requested`](crate::l2_book::tokio::BookWriter::snapshot_requested).
+    pub fn new_coinbase(product_id: impl Into<String>, depth: usize, interval: Duration) -> AsyncBook<SequenceNum> {
+        AsyncBook::new_streamed(product_id.into(), CoinbaseBookSequencer, depth, interval)
+    }
+}
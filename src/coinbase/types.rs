@@ -0,0 +1,142 @@
+use crate::l2_book;
+use crate::l2_book::types::parse_rfc3339_ms;
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+
+/// Any message of the connection, `sequence_num` is shared by every channel
+#[derive(Deserialize, Debug)]
+pub struct Message<'a> {
+    #[serde(borrow)]
+    pub channel: std::borrow::Cow<'a, str>,
+
+    #[serde(borrow)]
+    pub timestamp: std::borrow::Cow<'a, str>,
+
+    pub sequence_num: u64,
+
+    #[serde(default)]
+    #[serde(borrow)]
+    pub events: Vec<Event<'a>>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum Event<'a> {
+    #[serde(borrow)]
+    L2(L2Event<'a>),
+    Other(IgnoredAny),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EventType {
+    Snapshot,
+    Update,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct L2Event<'a> {
+    #[serde(rename = "type")]
+    pub kind: EventType,
+
+    #[serde(borrow)]
+    pub product_id: std::borrow::Cow<'a, str>,
+
+    pub updates: Vec<L2Update>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Bid,
+    Offer,
+}
+
+/// `new_quantity` is the absolute size of the level
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct L2Update {
+    pub side: Side,
+    pub price_level: l2_book::Price,
+    pub new_quantity: l2_book::Size,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SequenceNum {
+    pub sequence_num: u64,
+}
+
+impl Message<'_> {
+    /// Order for the book of `product_id`. Every message, even from another channel or
+    /// product, has to reach the book so the `sequence_num` chain stays unbroken, one
+    /// parsed message serves every book of the connection.
+    pub fn to_order(&self, product_id: &str) -> l2_book::Order<SequenceNum> {
+        let mut order = l2_book::Order {
+            id: l2_book::Sequence(self.sequence_num),
+            bids: vec![],
+            asks: vec![],
+            is_snapshot: false,
+            ts_ms: parse_rfc3339_ms(&self.timestamp).unwrap_or_default(),
+            o: SequenceNum {
+                sequence_num: self.sequence_num,
+            },
+        };
+
+        let events = self.events.iter().filter_map(|e| match e {
+            Event::L2(e) if e.product_id == product_id => Some(e),
+            _ => None,
+        });
+
+        for event in events {
+            order.is_snapshot |= event.kind == EventType::Snapshot;
+
+            for update in event.updates.iter() {
+                let pxsz = l2_book::PriceSize(update.price_level, update.new_quantity);
+                match update.side {
+                    Side::Bid => order.bids.push(pxsz),
+                    Side::Offer => order.asks.push(pxsz),
+                }
+            }
+        }
+
+        order
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Message;
+    use crate::l2_book::{Price, PriceSize, Size};
+
+    #[test]
+    fn deserialize_snapshot() {
+        let d = r#"{"channel":"l2_data","client_id":"","timestamp":"2023-02-09T20:32:50.714964855Z","sequence_num":0,"events":[{"type":"snapshot","product_id":"BTC-USD","updates":[{"side":"bid","event_time":"1970-01-01T00:00:00Z","price_level":"21921.73","new_quantity":"0.06317902"},{"side":"offer","event_time":"1970-01-01T00:00:00Z","price_level":"21921.74","new_quantity":"1.5"}]}]}"#;
+        let order = serde_json::from_str::<Message>(d).unwrap().to_order("BTC-USD");
+
+        assert!(order.is_snapshot);
+        assert_eq!(order.id.val(), 0);
+        assert_eq!(order.ts_ms, 1675974770714);
+        assert_eq!(order.bids, vec![PriceSize(Price::from(21921.73), Size::from(0.06317902))]);
+        assert_eq!(order.asks, vec![PriceSize(Price::from(21921.74), Size::from(1.5))]);
+    }
+
+    #[test]
+    fn other_channels_keep_sequence() {
+        let d = r#"{"channel":"subscriptions","client_id":"","timestamp":"2023-02-09T20:32:50.714964855Z","sequence_num":1,"events":[{"subscriptions":{"level2":["BTC-USD"]}}]}"#;
+        let order = serde_json::from_str::<Message>(d).unwrap().to_order("BTC-USD");
+
+        assert!(!order.is_snapshot);
+        assert_eq!(order.id.val(), 1);
+        assert!(order.bids.is_empty() && order.asks.is_empty());
+    }
+
+    #[test]
+    fn one_message_for_every_product() {
+        let d = r#"{"channel":"l2_data","client_id":"","timestamp":"2023-02-09T20:32:50.714964855Z","sequence_num":2,"events":[{"type":"update","product_id":"BTC-USD","updates":[{"side":"bid","event_time":"2023-02-09T20:32:50.714964855Z","price_level":"21921.73","new_quantity":"0"}]},{"type":"update","product_id":"ETH-USD","updates":[{"side":"offer","event_time":"2023-02-09T20:32:50.714964855Z","price_level":"1650.5","new_quantity":"2"}]}]}"#;
+        let msg = serde_json::from_str::<Message>(d).unwrap();
+
+        let (btc, eth) = (msg.to_order("BTC-USD"), msg.to_order("ETH-USD"));
+        assert_eq!((btc.id.val(), eth.id.val()), (2, 2));
+        assert_eq!(btc.bids, vec![PriceSize(Price::from(21921.73), Size(0))]);
+        assert_eq!(eth.asks, vec![PriceSize(Price::from(1650.5), Size::from(2.0))]);
+    }
+}
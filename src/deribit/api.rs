@@ -0,0 +1,52 @@
+use super::types::{OrderBookResponse, OrderBookSnapshot};
+
+/// Deribit API
+pub struct Deribit;
+
+impl Deribit {
+    pub fn rest_url(&self) -> &str {
+        "https://www.deribit.com/api/v2"
+    }
+
+    pub fn ws_url(&self) -> &str {
+        "wss://www.deribit.com/ws/api/v2"
+    }
+
+    pub async fn get_order_book(&self, instrument_name: &str) -> Result<OrderBookSnapshot, reqwest::Error> {
+        let client = reqwest::Client::new();
+        let url = format!("{}/public/get_order_book", self.rest_url());
+
+        let resp = client
+            .get(&url)
+            .query(&[("instrument_name", instrument_name), ("depth", "10000")])
+            .send()
+            .await?;
+
+        let data = resp.json::<OrderBookResponse>().await?;
+        Ok(data.result)
+    }
+}
+
+/// Raw book channel, e.g. `book.BTC-PERPETUAL.raw`. Raw channels need an authorized connection.
+pub fn book_channel(instrument_name: &str) -> String {
+    format!("book.{}.raw", instrument_name)
+}
+
+/// `public/subscribe` request, a single request can carry the channels of many instruments
+pub fn subscribe(id: u64, channels: &[String]) -> String {
+    request(id, "public/subscribe", channels)
+}
+
+pub fn unsubscribe(id: u64, channels: &[String]) -> String {
+    request(id, "public/unsubscribe", channels)
+}
+
+fn request(id: u64, method: &str, channels: &[String]) -> String {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": method,
+        "params": { "channels": channels },
+    })
+    .to_string()
+}
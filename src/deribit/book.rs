@@ -0,0 +1,50 @@
+use super::api::Deribit;
+use super::types::ChangeIds;
+use crate::l2_book::tokio::{Book as AsyncBook, SnapshotFetcher};
+use crate::l2_book::{BookSequencer, Order, Sequence};
+use std::time::Duration;
+
+/// Each change chains on the previous one with `prev_change_id`, ids are not contiguous
+struct DeribitBookSequencer;
+
+impl BookSequencer<ChangeIds> for DeribitBookSequencer {
+    fn is_first_event(&self, cur_seq: Sequence, update: &Order<ChangeIds>) -> bool {
+        update.o.prev_change_id == Some(cur_seq.val())
+    }
+
+    fn is_stale(&self, cur_seq: Sequence, update: &Order<ChangeIds>) -> bool {
+        update.o.prev_change_id.is_some_and(|prev| prev > cur_seq.val())
+    }
+
+    fn is_next(&self, cur_seq: Sequence, update: &Order<ChangeIds>) -> bool {
+        update.o.prev_change_id == Some(cur_seq.val())
+    }
+}
+
+pub struct DeribitSnapshotFetcher {
+    api: Deribit,
+}
+
+impl SnapshotFetcher<ChangeIds> for DeribitSnapshotFetcher {
+    type Error = reqwest::Error;
+
+    async fn fetch_snapshot(&self, instrument_name: &str) -> Result<Order<ChangeIds>, Self::Error> {
+        Ok(self.api.get_order_book(instrument_name).await?.into())
+    }
+}
+
+pub struct Book;
+
+impl Book {
+    /// Book of one instrument, the subscription snapshot is applied as is and
+    /// `public/get_order_book` is only fetched after a gap.
+    pub fn new_deribit(instrument_name: impl Into<String>, depth: usize, interval: Duration) -> AsyncBook<ChangeIds> {
+        AsyncBook::new(
+            instrument_name.into(),
+            DeribitBookSequencer,
+            DeribitSnapshotFetcher { api: Deribit },
+            depth,
+            interval,
+        )
+    }
+}
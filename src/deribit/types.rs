@@ -0,0 +1,148 @@
+use crate::l2_book;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Debug)]
+pub struct Notification<'a> {
+    #[serde(borrow)]
+    pub method: std::borrow::Cow<'a, str>,
+
+    #[serde(borrow)]
+    pub params: NotificationParams<'a>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct NotificationParams<'a> {
+    #[serde(borrow)]
+    pub channel: std::borrow::Cow<'a, str>,
+
+    #[serde(borrow)]
+    pub data: BookData<'a>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BookDataType {
+    Snapshot,
+    Change,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BookData<'a> {
+    #[serde(rename = "type")]
+    pub kind: BookDataType,
+
+    #[serde(borrow)]
+    pub instrument_name: std::borrow::Cow<'a, str>,
+
+    pub timestamp: u64,
+
+    pub bids: Vec<Level>,
+
+    pub asks: Vec<Level>,
+
+    #[serde(flatten)]
+    pub seq: ChangeIds,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LevelAction {
+    New,
+    Change,
+    Delete,
+}
+
+/// `[action, price, amount]`
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Level(pub LevelAction, pub l2_book::Price, pub l2_book::Size);
+
+impl From<Level> for l2_book::PriceSize {
+    fn from(val: Level) -> Self {
+        match val.0 {
+            LevelAction::Delete => l2_book::PriceSize(val.1, l2_book::types::ZERO_SIZE),
+            LevelAction::New | LevelAction::Change => l2_book::PriceSize(val.1, val.2),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChangeIds {
+    pub change_id: u64,
+
+    /// Missing on snapshots
+    pub prev_change_id: Option<u64>,
+}
+
+impl<'a> From<BookData<'a>> for l2_book::Order<ChangeIds> {
+    fn from(val: BookData<'a>) -> Self {
+        l2_book::Order {
+            id: l2_book::Sequence(val.seq.change_id),
+            bids: val.bids.into_iter().map(Into::into).collect(),
+            asks: val.asks.into_iter().map(Into::into).collect(),
+            is_snapshot: val.kind == BookDataType::Snapshot,
+            ts_ms: val.timestamp,
+            o: val.seq,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct OrderBookResponse {
+    pub result: OrderBookSnapshot,
+}
+
+/// `public/get_order_book` result
+#[derive(Deserialize, Debug)]
+pub struct OrderBookSnapshot {
+    pub instrument_name: String,
+    pub timestamp: u64,
+    pub change_id: u64,
+    pub bids: Vec<l2_book::PriceSize>,
+    pub asks: Vec<l2_book::PriceSize>,
+}
+
+impl From<OrderBookSnapshot> for l2_book::Order<ChangeIds> {
+    fn from(val: OrderBookSnapshot) -> Self {
+        l2_book::Order {
+            id: l2_book::Sequence(val.change_id),
+            bids: val.bids,
+            asks: val.asks,
+            is_snapshot: true,
+            ts_ms: val.timestamp,
+            o: ChangeIds {
+                change_id: val.change_id,
+                prev_change_id: None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BookDataType, Notification, OrderBookResponse};
+    use crate::l2_book::{Order, Price, PriceSize, Size};
+
+    #[test]
+    fn deserialize_change() {
+        let d = r#"{"jsonrpc":"2.0","method":"subscription","params":{"channel":"book.BTC-PERPETUAL.raw","data":{"type":"change","timestamp":1554373911330,"prev_change_id":297217,"instrument_name":"BTC-PERPETUAL","change_id":297218,"bids":[["delete",5032.0,0.0]],"asks":[["new",5043.5,20.0],["change",5044.0,40.0]]}}}"#;
+        let msg: Notification = serde_json::from_str(d).unwrap();
+
+        assert_eq!(msg.params.data.kind, BookDataType::Change);
+        assert_eq!(msg.params.data.seq.prev_change_id, Some(297217));
+
+        let order: Order<_> = msg.params.data.into();
+        assert!(!order.is_snapshot);
+        assert_eq!(order.bids, vec![PriceSize(Price::from(5032.0), Size(0))]);
+        assert_eq!(order.asks[1], PriceSize(Price::from(5044.0), Size::from(40.0)));
+    }
+
+    #[test]
+    fn deserialize_order_book() {
+        let d = r#"{"jsonrpc":"2.0","id":1,"result":{"timestamp":1554375447971,"stats":{"volume":0.0},"state":"open","instrument_name":"BTC-PERPETUAL","change_id":297223,"bids":[[5042.5,20.0]],"asks":[[5043.5,10.0]],"best_bid_price":5042.5}}"#;
+        let order: Order<_> = serde_json::from_str::<OrderBookResponse>(d).unwrap().result.into();
+
+        assert!(order.is_snapshot);
+        assert_eq!(order.id.val(), 297223);
+        assert_eq!(order.o.prev_change_id, None);
+    }
+}
@@ -0,0 +1,15 @@
+pub const WS_URL: &str = "wss://indexer.dydx.trade/v4/ws";
+
+/// Subscribe request for the `v4_orderbook` channel of a market, e.g. `BTC-USD`
+pub fn subscribe(market: &str) -> String {
+    request("subscribe", market)
+}
+
+pub fn unsubscribe(market: &str) -> String {
+    request("unsubscribe", market)
+}
+
+fn request(kind: &str, market: &str) -> String {
+    serde_json::json!({ "type": kind, "channel": "v4_orderbook", "id": market })
+        .to_string()
+}
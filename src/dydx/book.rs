@@ -0,0 +1,75 @@
+use super::types::MessageId;
+use crate::l2_book::tokio::Book as AsyncBook;
+use crate::l2_book::{BookSequencer, Order, Sequence, UncrossPolicy};
+use std::time::Duration;
+
+/// `message_id` is shared by every channel of the connection, so it only has to move forward
+struct DydxBookSequencer {
+    uncross: UncrossPolicy,
+}
+
+impl BookSequencer<MessageId> for DydxBookSequencer {
+    fn is_first_event(&self, cur_seq: Sequence, update: &Order<MessageId>) -> bool {
+        cur_seq.val() < update.o.message_id
+    }
+
+    fn is_stale(&self, _cur_seq: Sequence, _update: &Order<MessageId>) -> bool {
+        false
+    }
+
+    fn is_next(&self, cur_seq: Sequence, update: &Order<MessageId>) -> bool {
+        cur_seq.val() < update.o.message_id
+    }
+
+    fn uncross_policy(&self) -> UncrossPolicy {
+        self.uncross
+    }
+}
+
+pub struct Book;
+
+impl Book {
+    /// Streamed book, resubscribe to the market on
+    /// [`snapshot_requested`](crate::l2_book::tokio::BookWriter::snapshot_requested).
+    /// The indexer can leave stale crossed levels, [`UncrossPolicy::DropOlder`] removes them.
+    pub fn new_dydx(market: impl Into<String>, depth: usize, uncross: UncrossPolicy, interval: Duration) -> AsyncBook<MessageId> {
+        AsyncBook::new_streamed(market.into(), DydxBookSequencer { uncross }, depth, interval)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DydxBookSequencer;
+    use crate::dydx::types::MessageId;
+    use crate::l2_book::{BookAction, BookFsm, Order, Price, PriceSize, Sequence, Size, UncrossPolicy};
+
+    fn order(message_id: u64, is_snapshot: bool, bids: &[(f64, f64)], asks: &[(f64, f64)]) -> Order<MessageId> {
+        let levels = |l: &[(f64, f64)]| l.iter().map(|&(p, s)| PriceSize(Price::from(p), Size::from(s))).collect();
+
+        Order {
+            id: Sequence(message_id),
+            bids: levels(bids),
+            asks: levels(asks),
+            is_snapshot,
+            ts_ms: 0,
+            o: MessageId { message_id },
+        }
+    }
+
+    #[test]
+    fn drops_older_crossed_side() {
+        let mut fsm = BookFsm::new(DydxBookSequencer {
+            uncross: UncrossPolicy::DropOlder,
+        });
+
+        let snapshot = order(1, true, &[(99.0, 1.0)], &[(100.0, 1.0), (101.0, 1.0), (102.0, 1.0)]);
+        assert_eq!(BookAction::Ok, fsm.update(snapshot));
+
+        // Fresh bid crosses two stale asks
+        assert_eq!(BookAction::Ok, fsm.update(order(2, false, &[(101.0, 2.0)], &[])));
+
+        let book = fsm.snapshot(10);
+        assert_eq!(book.bids[0], (Price::from(101.0), Size::from(2.0)));
+        assert_eq!(book.asks, vec![(Price::from(102.0), Size::from(1.0))]);
+    }
+}
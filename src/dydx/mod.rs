@@ -0,0 +1,5 @@
+pub mod api;
+pub mod book;
+pub mod types;
+
+pub use book::Book;
@@ -0,0 +1,100 @@
+use crate::l2_book;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageType {
+    Subscribed,
+    ChannelData,
+}
+
+/// `subscribed` snapshot or `channel_data` update of the `v4_orderbook` channel
+#[derive(Deserialize, Debug)]
+pub struct OrderbookMessage<'a> {
+    #[serde(rename = "type")]
+    pub kind: MessageType,
+
+    #[serde(borrow)]
+    pub channel: std::borrow::Cow<'a, str>,
+
+    /// Market, e.g. `BTC-USD`
+    #[serde(borrow)]
+    pub id: std::borrow::Cow<'a, str>,
+
+    pub message_id: u64,
+
+    pub contents: Contents,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Contents {
+    #[serde(default)]
+    pub bids: Vec<Level>,
+
+    #[serde(default)]
+    pub asks: Vec<Level>,
+}
+
+/// Snapshots send `{"price", "size"}` objects while updates send `[price, size]`,
+/// a size of `"0"` removes the level.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Level {
+    Object { price: l2_book::Price, size: l2_book::Size },
+    Pair(l2_book::Price, l2_book::Size),
+}
+
+impl From<Level> for l2_book::PriceSize {
+    fn from(val: Level) -> Self {
+        match val {
+            Level::Object { price, size } | Level::Pair(price, size) => l2_book::PriceSize(price, size),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageId {
+    pub message_id: u64,
+}
+
+impl<'a> From<OrderbookMessage<'a>> for l2_book::Order<MessageId> {
+    fn from(val: OrderbookMessage<'a>) -> Self {
+        l2_book::Order {
+            id: l2_book::Sequence(val.message_id),
+            bids: val.contents.bids.into_iter().map(Into::into).collect(),
+            asks: val.contents.asks.into_iter().map(Into::into).collect(),
+            is_snapshot: val.kind == MessageType::Subscribed,
+            // The indexer does not timestamp orderbook messages
+            ts_ms: 0,
+            o: MessageId {
+                message_id: val.message_id,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::OrderbookMessage;
+    use crate::l2_book::{Order, Price, PriceSize, Size};
+
+    #[test]
+    fn deserialize_subscribed() {
+        let d = r#"{"type":"subscribed","connection_id":"0f2c8a1e","message_id":1,"channel":"v4_orderbook","id":"BTC-USD","contents":{"bids":[{"price":"65000","size":"1.2"}],"asks":[{"price":"65010","size":"0.5"}]}}"#;
+        let order: Order<_> = serde_json::from_str::<OrderbookMessage>(d).unwrap().into();
+
+        assert!(order.is_snapshot);
+        assert_eq!(order.bids, vec![PriceSize(Price::from(65000.0), Size::from(1.2))]);
+    }
+
+    #[test]
+    fn deserialize_channel_data() {
+        let d = r#"{"type":"channel_data","connection_id":"0f2c8a1e","message_id":5,"id":"BTC-USD","channel":"v4_orderbook","version":"1.0.0","contents":{"asks":[["65010","0"]]}}"#;
+        let order: Order<_> = serde_json::from_str::<OrderbookMessage>(d).unwrap().into();
+
+        assert!(!order.is_snapshot);
+        assert_eq!(order.id.val(), 5);
+        assert!(order.bids.is_empty());
+        assert_eq!(order.asks, vec![PriceSize(Price::from(65010.0), Size(0))]);
+    }
+}
@@ -0,0 +1,183 @@
+use crate::l2_book::tokio::BookWriter;
+use crate::l2_book::{Order, Price, Size};
+use crate::ws::{Message, WsError, WsHandle};
+use std::fmt;
+use tokio::sync::mpsc;
+
+/// Maps raw frames of one connection to typed events
+pub trait Decoder: Send + 'static {
+    /// Sequencing data carried by the decoded orders
+    type Seq: Send + 'static;
+
+    /// Pushes the events of one frame, a frame can hold none or several.
+    /// The frame is mutable so in-place parsers such as simd-json can be used.
+    fn decode(&mut self, frame: &mut [u8], events: &mut Vec<Event<Self::Seq>>);
+}
+
+#[derive(Debug)]
+pub enum Event<O> {
+    /// Book diff or snapshot
+    Book(Order<O>),
+    Trade(Trade),
+    Control(Control),
+    Error(FeedError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TakerSide {
+    Buy,
+    Sell,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Trade {
+    pub price: Price,
+    pub size: Size,
+    pub side: TakerSide,
+    pub ts_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Control {
+    /// Subscription acks, pongs and other venue messages carrying no market data
+    Message(Vec<u8>),
+    /// Frame to send back on the connection, e.g. an application level pong
+    Reply(Message),
+}
+
+#[derive(Debug)]
+pub enum FeedError {
+    /// Frame that couldn't be decoded, the book was reset in case it carried an update
+    Decode(String),
+    /// Well-formed message of an event type the decoder doesn't handle
+    UnknownEvent(String),
+    /// Book payload of a stream no book is routed to
+    UnknownStream(String),
+    /// Connection dropped, the book was reset and waits for the reconnected stream
+    Disconnected(WsError),
+    /// Connection is gone for good
+    Closed,
+}
+
+impl fmt::Display for FeedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeedError::Decode(e) => write!(f, "Decode error: {}", e),
+            FeedError::UnknownEvent(e) => write!(f, "Unknown event: {}", e),
+            FeedError::UnknownStream(s) => write!(f, "Unknown stream: {}", s),
+            FeedError::Disconnected(e) => write!(f, "Disconnected: {}", e),
+            FeedError::Closed => write!(f, "Connection closed"),
+        }
+    }
+}
+
+impl std::error::Error for FeedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FeedError::Disconnected(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl<O> Event<O> {
+    pub fn decode_error(e: impl fmt::Display) -> Self {
+        Event::Error(FeedError::Decode(e.to_string()))
+    }
+}
+
+/// Spawns a task owning the connection: book events go to `writer`, replies back on the
+/// connection and everything else to the returned receiver. `resubscribe` is sent whenever
+/// the book asks for a snapshot from the stream.
+///
+/// Each connection error resets the book, so with [`crate::ws::connect_reconnecting`] it is
+/// rebuilt from a new snapshot once the connection is back. So does each frame that fails to
+/// decode, as it may have been a lost book update.
+///
+/// The task never waits on the receiver, events are dropped while it is full or gone.
+/// The last event is always a [`FeedError::Closed`], which waits for room instead.
+pub fn spawn_feed<D: Decoder>(ws: WsHandle, decoder: D, writer: BookWriter<D::Seq>, resubscribe: Vec<Message>) -> mpsc::Receiver<Event<D::Seq>> {
+    let (events_tx, events_rx) = mpsc::channel(1000);
+    tokio::spawn(feed(ws, decoder, writer, resubscribe, events_tx));
+    events_rx
+}
+
+async fn feed<D: Decoder>(
+    mut ws: WsHandle,
+    mut decoder: D,
+    writer: BookWriter<D::Seq>,
+    resubscribe: Vec<Message>,
+    events_tx: mpsc::Sender<Event<D::Seq>>,
+) {
+    let mut events = Vec::new();
+
+    'feed: loop {
+        let mut frame = tokio::select! {
+            res = ws.rx.recv() => match res {
+                Some(Ok(frame)) => frame,
+                Some(Err(e)) => {
+                    writer.reset().await;
+                    let _ = events_tx.try_send(Event::Error(FeedError::Disconnected(e)));
+                    continue;
+                }
+                None => break,
+            },
+            _ = writer.snapshot_requested() => {
+                for msg in resubscribe.iter() {
+                    // The connection task is gone, no more frames will come
+                    if ws.tx.send(msg.clone()).await.is_err() {
+                        break 'feed;
+                    }
+                }
+                continue;
+            }
+        };
+
+        decoder.decode(&mut frame, &mut events);
+
+        for event in events.drain(..) {
+            match event {
+                Event::Book(order) => writer.update(order).await,
+                Event::Control(Control::Reply(msg)) => {
+                    let _ = ws.tx.send(msg).await;
+                }
+                Event::Error(FeedError::Decode(e)) => {
+                    // The frame may have been a book update, sequencers only checking that ids
+                    // move forward would never notice it is missing
+                    writer.reset().await;
+                    let _ = events_tx.try_send(Event::Error(FeedError::Decode(e)));
+                }
+                event => {
+                    let _ = events_tx.try_send(event);
+                }
+            }
+        }
+    }
+
+    let _ = events_tx.send(Event::Error(FeedError::Closed)).await;
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Event, FeedError, spawn_feed};
+    use crate::bybit::decode::OrderbookDecoder;
+    use crate::l2_book::tokio::{BookMessage, BookWriter};
+    use crate::ws::WsHandle;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn decode_error_resets_book() {
+        let (frames_tx, rx) = mpsc::channel(10);
+        let (tx, _requests) = mpsc::channel(10);
+        let (writer, mut book_rx) = BookWriter::channel();
+
+        let mut events = spawn_feed(WsHandle { rx, tx }, OrderbookDecoder, writer, vec![]);
+
+        frames_tx.send(Ok(br#"{"topic":"orderbook.50.BTCUSDT","type":"delta","data":"#.to_vec())).await.unwrap();
+        assert!(matches!(book_rx.recv().await, Some(BookMessage::Reset)));
+        assert!(matches!(events.recv().await, Some(Event::Error(FeedError::Decode(_)))));
+
+        drop(frames_tx);
+        assert!(matches!(events.recv().await, Some(Event::Error(FeedError::Closed))));
+    }
+}
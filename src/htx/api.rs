@@ -0,0 +1,62 @@
+use super::types::Ping;
+use flate2::read::GzDecoder;
+use std::io::Read;
+
+/// Market-by-price feed, incremental `mbp` channels are only served here
+pub const WS_URL: &str = "wss://api.huobi.pro/feed";
+
+/// `mbp` channel of a symbol, e.g. `btcusdt`. `levels` is `5`, `20`, `150` or `400`.
+pub fn mbp_topic(symbol: &str, levels: u32) -> String {
+    format!("market.{}.mbp.{}", symbol, levels)
+}
+
+pub fn subscribe(symbol: &str, levels: u32) -> String {
+    request("sub", symbol, levels)
+}
+
+pub fn unsubscribe(symbol: &str, levels: u32) -> String {
+    request("unsub", symbol, levels)
+}
+
+/// One-off `req` for the full `mbp` book, answered with an [`MbpSnapshot`](super::types::MbpSnapshot)
+pub fn request_snapshot(symbol: &str, levels: u32) -> String {
+    request("req", symbol, levels)
+}
+
+fn request(op: &str, symbol: &str, levels: u32) -> String {
+    let topic = mbp_topic(symbol, levels);
+
+    serde_json::json!({ op: topic, "id": topic }).to_string()
+}
+
+/// Every frame is gzip-compressed
+pub fn decompress(frame: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut buf = Vec::with_capacity(frame.len() * 4);
+    GzDecoder::new(frame).read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+/// `{"pong": ts}` answer when `msg` is an application ping, the connection is dropped if it isn't sent
+pub fn pong(msg: &[u8]) -> Option<String> {
+    let Ping { ping } = serde_json::from_slice(msg).ok()?;
+    Some(serde_json::json!({ "pong": ping }).to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decompress, pong};
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+
+    #[test]
+    fn decompress_ping() {
+        let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+        enc.write_all(br#"{"ping":1492420473027}"#).unwrap();
+        let frame = enc.finish().unwrap();
+
+        let msg = decompress(&frame).unwrap();
+        assert_eq!(pong(&msg).unwrap(), r#"{"pong":1492420473027}"#);
+        assert_eq!(pong(br#"{"ch":"market.btcusdt.mbp.150"}"#), None);
+    }
+}
@@ -0,0 +1,31 @@
+use super::types::MbpSeq;
+use crate::l2_book::tokio::Book as AsyncBook;
+use crate::l2_book::{BookSequencer, Order, Sequence};
+use std::time::Duration;
+
+/// Each update chains on the previous `seqNum` with `prevSeqNum`, numbers are not contiguous
+struct HtxBookSequencer;
+
+impl BookSequencer<MbpSeq> for HtxBookSequencer {
+    fn is_first_event(&self, cur_seq: Sequence, update: &Order<MbpSeq>) -> bool {
+        update.o.prev_seq_num == cur_seq.val()
+    }
+
+    fn is_stale(&self, cur_seq: Sequence, update: &Order<MbpSeq>) -> bool {
+        update.o.prev_seq_num > cur_seq.val()
+    }
+
+    fn is_next(&self, cur_seq: Sequence, update: &Order<MbpSeq>) -> bool {
+        update.o.prev_seq_num == cur_seq.val()
+    }
+}
+
+pub struct Book;
+
+impl Book {
+    /// Streamed book, send [`request_snapshot`](super::api::request_snapshot) after subscribing
+    /// and again on [`snapshot_requested`](crate::l2_book::tokio::BookWriter::snapshot_requested).
+    pub fn new_htx(symbol: impl Into<String>, depth: usize, interval: Duration) -> AsyncBook<MbpSeq> {
+        AsyncBook::new_streamed(symbol.into(), HtxBookSequencer, depth, interval)
+    }
+}
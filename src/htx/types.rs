@@ -0,0 +1,110 @@
+use crate::l2_book;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ping {
+    pub ping: u64,
+}
+
+/// Incremental `market.$symbol.mbp.$levels` push
+#[derive(Deserialize, Debug)]
+pub struct MbpUpdate<'a> {
+    #[serde(borrow)]
+    pub ch: std::borrow::Cow<'a, str>,
+
+    pub ts: u64,
+
+    pub tick: MbpTick,
+}
+
+/// Answer to a `req` on the `mbp` channel
+#[derive(Deserialize, Debug)]
+pub struct MbpSnapshot<'a> {
+    #[serde(borrow)]
+    pub rep: std::borrow::Cow<'a, str>,
+
+    #[serde(borrow)]
+    pub status: std::borrow::Cow<'a, str>,
+
+    #[serde(default)]
+    pub ts: u64,
+
+    pub data: MbpTick,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct MbpTick {
+    #[serde(default)]
+    pub bids: Vec<l2_book::PriceSize>,
+
+    #[serde(default)]
+    pub asks: Vec<l2_book::PriceSize>,
+
+    #[serde(flatten)]
+    pub seq: MbpSeq,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MbpSeq {
+    #[serde(rename = "seqNum")]
+    pub seq_num: u64,
+
+    /// Missing on `req` snapshots
+    #[serde(rename = "prevSeqNum")]
+    #[serde(default)]
+    pub prev_seq_num: u64,
+}
+
+impl From<MbpUpdate<'_>> for l2_book::Order<MbpSeq> {
+    fn from(val: MbpUpdate<'_>) -> Self {
+        l2_book::Order {
+            id: l2_book::Sequence(val.tick.seq.seq_num),
+            bids: val.tick.bids,
+            asks: val.tick.asks,
+            is_snapshot: false,
+            ts_ms: val.ts,
+            o: val.tick.seq,
+        }
+    }
+}
+
+impl From<MbpSnapshot<'_>> for l2_book::Order<MbpSeq> {
+    fn from(val: MbpSnapshot<'_>) -> Self {
+        l2_book::Order {
+            id: l2_book::Sequence(val.data.seq.seq_num),
+            bids: val.data.bids,
+            asks: val.data.asks,
+            is_snapshot: true,
+            ts_ms: val.ts,
+            o: val.data.seq,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MbpSnapshot, MbpUpdate};
+    use crate::l2_book::{Order, Price, PriceSize, Size};
+
+    #[test]
+    fn deserialize_update() {
+        let d = r#"{"ch":"market.btcusdt.mbp.150","ts":1573199608679,"tick":{"seqNum":100020146795,"prevSeqNum":100020146794,"asks":[[645.140000000000000000,26.755973959140651643]]}}"#;
+        let msg: MbpUpdate = serde_json::from_str(d).unwrap();
+
+        let order: Order<_> = msg.into();
+        assert_eq!(order.o.prev_seq_num, 100020146794);
+        assert!(order.bids.is_empty());
+        assert_eq!(order.asks[0].0, Price::from(645.14));
+    }
+
+    #[test]
+    fn deserialize_snapshot() {
+        let d = r#"{"id":"market.btcusdt.mbp.150","rep":"market.btcusdt.mbp.150","status":"ok","data":{"seqNum":100020142010,"bids":[[618.37,71.594]],"asks":[[645.14,26.75]]}}"#;
+        let msg: MbpSnapshot = serde_json::from_str(d).unwrap();
+
+        let order: Order<_> = msg.into();
+        assert!(order.is_snapshot);
+        assert_eq!(order.id.val(), 100020142010);
+        assert_eq!(order.bids[0], PriceSize(Price::from(618.37), Size::from(71.594)));
+    }
+}
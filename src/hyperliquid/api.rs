@@ -0,0 +1,15 @@
+pub const WS_URL: &str = "wss://api.hyperliquid.xyz/ws";
+
+/// Subscribe request for the `l2Book` of a coin, e.g. `BTC`
+pub fn subscribe(coin: &str) -> String {
+    request("subscribe", coin)
+}
+
+pub fn unsubscribe(coin: &str) -> String {
+    request("unsubscribe", coin)
+}
+
+fn request(method: &str, coin: &str) -> String {
+    serde_json::json!({ "method": method, "subscription": { "type": "l2Book", "coin": coin } })
+        .to_string()
+}
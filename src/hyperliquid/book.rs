@@ -0,0 +1,12 @@
+use super::types::OrderCounts;
+use crate::l2_book::tokio::Book as AsyncBook;
+use std::time::Duration;
+
+pub struct Book;
+
+impl Book {
+    /// Snapshot-only book, every `l2Book` message replaces the book
+    pub fn new_hyperliquid(coin: impl Into<String>, depth: usize, interval: Duration) -> AsyncBook<OrderCounts> {
+        AsyncBook::new_snapshot_only(coin.into(), depth, interval)
+    }
+}
@@ -0,0 +1,76 @@
+use crate::l2_book;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct L2BookMessage<'a> {
+    #[serde(borrow)]
+    pub channel: std::borrow::Cow<'a, str>,
+
+    #[serde(borrow)]
+    pub data: L2Book<'a>,
+}
+
+/// Complete top-N book, `levels` holds the bids then the asks
+#[derive(Deserialize, Debug)]
+pub struct L2Book<'a> {
+    #[serde(borrow)]
+    pub coin: std::borrow::Cow<'a, str>,
+
+    pub time: u64,
+
+    pub levels: (Vec<Level>, Vec<Level>),
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Level {
+    pub px: l2_book::Price,
+    pub sz: l2_book::Size,
+    /// Number of orders at the level
+    pub n: u32,
+}
+
+/// Order counts of each level, in the same order as the book levels
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct OrderCounts {
+    pub bids: Vec<u32>,
+    pub asks: Vec<u32>,
+}
+
+impl<'a> From<L2Book<'a>> for l2_book::Order<OrderCounts> {
+    fn from(val: L2Book<'a>) -> Self {
+        let (bids, asks) = val.levels;
+
+        l2_book::Order {
+            id: l2_book::Sequence(val.time),
+            bids: bids.iter().map(|l| l2_book::PriceSize(l.px, l.sz)).collect(),
+            asks: asks.iter().map(|l| l2_book::PriceSize(l.px, l.sz)).collect(),
+            is_snapshot: true,
+            ts_ms: val.time,
+            o: OrderCounts {
+                bids: bids.iter().map(|l| l.n).collect(),
+                asks: asks.iter().map(|l| l.n).collect(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::L2BookMessage;
+    use crate::l2_book::{Order, Price, PriceSize, Size};
+
+    #[test]
+    fn deserialize_l2_book() {
+        let d = r#"{"channel":"l2Book","data":{"coin":"BTC","time":1700000000000,"levels":[[{"px":"36950","sz":"1.5","n":3},{"px":"36949","sz":"0.2","n":1}],[{"px":"36951","sz":"2","n":5}]]}}"#;
+        let msg: L2BookMessage = serde_json::from_str(d).unwrap();
+        assert_eq!(msg.data.coin, "BTC");
+
+        let order: Order<_> = msg.data.into();
+        assert!(order.is_snapshot);
+        assert_eq!(order.ts_ms, 1700000000000);
+        assert_eq!(order.bids[0], PriceSize(Price::from(36950.0), Size::from(1.5)));
+        assert_eq!(order.asks[0], PriceSize(Price::from(36951.0), Size::from(2.0)));
+        assert_eq!(order.o.bids, vec![3, 1]);
+        assert_eq!(order.o.asks, vec![5]);
+    }
+}
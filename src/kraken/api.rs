@@ -0,0 +1,18 @@
+pub const WS_URL: &str = "wss://ws.kraken.com/v2";
+
+/// Subscribe request for the `book` channel, `depth` is one of 10, 25, 100, 500 or 1000
+pub fn subscribe(symbols: &[&str], depth: usize) -> String {
+    request("subscribe", symbols, depth)
+}
+
+pub fn unsubscribe(symbols: &[&str], depth: usize) -> String {
+    request("unsubscribe", symbols, depth)
+}
+
+fn request(method: &str, symbols: &[&str], depth: usize) -> String {
+    serde_json::json!({
+        "method": method,
+        "params": { "channel": "book", "symbol": symbols, "depth": depth },
+    })
+    .to_string()
+}
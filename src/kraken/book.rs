@@ -0,0 +1,124 @@
+use super::types::BookChecksum;
+use crate::l2_book::tokio::Book as AsyncBook;
+use crate::l2_book::{BookSequencer, Levels, Order, Sequence, Size};
+use std::time::Duration;
+
+/// Number of levels per side covered by the checksum
+const CHECKSUM_DEPTH: usize = 10;
+
+/// Decimal places of an instrument, as listed by the `instrument` channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Precision {
+    pub price: usize,
+    pub qty: usize,
+}
+
+/// Updates carry no sequence numbers, every update is applied and the checksum decides
+/// whether the book is still in sync. Levels pushed beyond the subscribed depth are
+/// not deleted by Kraken and have to be truncated locally.
+struct KrakenBookSequencer {
+    depth: usize,
+    precision: Precision,
+}
+
+impl BookSequencer<BookChecksum> for KrakenBookSequencer {
+    fn is_first_event(&self, _cur_seq: Sequence, _update: &Order<BookChecksum>) -> bool {
+        true
+    }
+
+    fn is_stale(&self, _cur_seq: Sequence, _update: &Order<BookChecksum>) -> bool {
+        false
+    }
+
+    fn is_next(&self, _cur_seq: Sequence, _update: &Order<BookChecksum>) -> bool {
+        true
+    }
+
+    fn is_valid(&self, book: &Levels<'_>, update: &Order<BookChecksum>) -> bool {
+        checksum(book, self.precision) == update.o.checksum
+    }
+
+    fn depth_limit(&self) -> Option<usize> {
+        Some(self.depth)
+    }
+}
+
+/// CRC32 of the top 10 asks then the top 10 bids, each level as price and quantity
+/// formatted to the instrument precision, without the decimal point and leading zeros.
+pub fn checksum(book: &Levels<'_>, precision: Precision) -> u32 {
+    let mut hasher = crc32fast::Hasher::new();
+
+    let asks = book.asks().take(CHECKSUM_DEPTH);
+    let bids = book.bids().take(CHECKSUM_DEPTH);
+
+    for (p, s) in asks.chain(bids) {
+        hasher.update(checksum_field(p.0, precision.price).as_bytes());
+        hasher.update(checksum_field(s.0, precision.qty).as_bytes());
+    }
+
+    hasher.finalize()
+}
+
+fn checksum_field(val: u64, precision: usize) -> String {
+    let s = format!("{:.*}", precision, Size(val)).replace('.', "");
+    s.trim_start_matches('0').to_string()
+}
+
+pub struct Book;
+
+impl Book {
+    /// Streamed book truncated to the subscribed `depth`, resubscribe on
+    /// [`snapshot_requested`](crate::l2_book::tokio::BookWriter::snapshot_requested)
+    pub fn new_kraken(symbol: impl Into<String>, depth: usize, precision: Precision, interval: Duration) -> AsyncBook<BookChecksum> {
+        AsyncBook::new_streamed(symbol.into(), KrakenBookSequencer { depth, precision }, depth, interval)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{KrakenBookSequencer, Precision, checksum_field};
+    use crate::kraken::types::BookChecksum;
+    use crate::l2_book::{BookAction, BookFsm, Order, Price, PriceSize, Sequence, Size};
+
+    fn order(is_snapshot: bool, checksum: u32, bids: &[(f64, f64)], asks: &[(f64, f64)]) -> Order<BookChecksum> {
+        let levels = |l: &[(f64, f64)]| l.iter().map(|&(p, s)| PriceSize(Price::from(p), Size::from(s))).collect();
+
+        Order {
+            id: Sequence(0),
+            bids: levels(bids),
+            asks: levels(asks),
+            is_snapshot,
+            ts_ms: 0,
+            o: BookChecksum { checksum },
+        }
+    }
+
+    #[test]
+    fn truncates_to_depth() {
+        let precision = Precision { price: 4, qty: 8 };
+        let mut fsm = BookFsm::new(KrakenBookSequencer { depth: 2, precision });
+
+        let asks = [(0.5668, 4410.79769357), (0.5669, 2400.0)];
+        let snapshot = order(true, 94847911, &[(0.5666, 4831.75496356), (0.5665, 1000.0)], &asks);
+        assert_eq!(BookAction::Ok, fsm.update(snapshot));
+
+        // New best bid pushes 0.5665 out of the subscribed depth without a delete
+        assert_eq!(BookAction::Ok, fsm.update(order(false, 3719854857, &[(0.5667, 1.0)], &[])));
+        assert_eq!(2, fsm.snapshot(10).bids.len());
+
+        // Levels beyond the depth don't change the checksum
+        assert_eq!(BookAction::Ok, fsm.update(order(false, 3719854857, &[(0.5664, 1.0)], &[])));
+        assert_eq!(BookAction::RetrieveSnapshot, fsm.update(order(false, 3719854857, &[(0.5667, 2.0)], &[])));
+    }
+
+    #[test]
+    fn checksum_fields_of_floored_floats() {
+        // JSON floats are floored when scaled, 0.0003 lands on 0.0002999999
+        let price: Price = serde_json::from_str("0.0003").unwrap();
+        assert_eq!(price, Price(2_999_999));
+        assert_eq!(checksum_field(price.0, 4), "3");
+
+        let qty: Size = serde_json::from_str("1098.3947558").unwrap();
+        assert_eq!(checksum_field(qty.0, 8), "109839475580");
+    }
+}
@@ -0,0 +1,94 @@
+use crate::l2_book;
+use crate::l2_book::types::parse_rfc3339_ms;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageType {
+    Snapshot,
+    Update,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BookMessage<'a> {
+    #[serde(borrow)]
+    pub channel: std::borrow::Cow<'a, str>,
+
+    #[serde(rename = "type")]
+    pub kind: MessageType,
+
+    #[serde(borrow)]
+    pub data: Vec<BookData<'a>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BookData<'a> {
+    #[serde(borrow)]
+    pub symbol: std::borrow::Cow<'a, str>,
+
+    pub bids: Vec<Level>,
+
+    pub asks: Vec<Level>,
+
+    pub checksum: u32,
+
+    #[serde(borrow)]
+    pub timestamp: Option<std::borrow::Cow<'a, str>>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Level {
+    pub price: l2_book::Price,
+    pub qty: l2_book::Size,
+}
+
+impl From<Level> for l2_book::PriceSize {
+    fn from(val: Level) -> Self {
+        l2_book::PriceSize(val.price, val.qty)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookChecksum {
+    pub checksum: u32,
+}
+
+impl From<BookMessage<'_>> for Vec<l2_book::Order<BookChecksum>> {
+    fn from(val: BookMessage<'_>) -> Self {
+        let is_snapshot = val.kind == MessageType::Snapshot;
+
+        val.data
+            .into_iter()
+            .map(|data| l2_book::Order {
+                // Kraken has no sequence numbers, integrity relies on the checksum
+                id: l2_book::Sequence(0),
+                bids: data.bids.into_iter().map(Into::into).collect(),
+                asks: data.asks.into_iter().map(Into::into).collect(),
+                is_snapshot,
+                ts_ms: data.timestamp.as_deref().and_then(parse_rfc3339_ms).unwrap_or_default(),
+                o: BookChecksum { checksum: data.checksum },
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BookMessage, MessageType};
+    use crate::l2_book::{Order, Price, PriceSize, Size};
+
+    #[test]
+    fn deserialize_update() {
+        let d = r#"{"channel":"book","type":"update","data":[{"symbol":"MATIC/USD","bids":[{"price":0.5657,"qty":1098.3947558}],"asks":[],"checksum":2114181697,"timestamp":"2023-10-06T17:35:55.440295Z"}]}"#;
+        let msg: BookMessage = serde_json::from_str(d).unwrap();
+
+        assert_eq!(msg.kind, MessageType::Update);
+        assert_eq!(msg.data[0].symbol, "MATIC/USD");
+
+        let orders: Vec<Order<_>> = msg.into();
+        assert!(!orders[0].is_snapshot);
+        assert_eq!(orders[0].ts_ms, 1696613755440);
+        assert_eq!(orders[0].o.checksum, 2114181697);
+        assert_eq!(orders[0].bids[0], PriceSize(Price::from(0.5657), Size::from(1098.3947558)));
+    }
+}
@@ -0,0 +1,59 @@
+use super::types::{ApiResponse, BulletData, Level2Snapshot};
+
+/// KuCoin spot API
+pub struct Kucoin;
+
+impl Kucoin {
+    pub fn rest_url(&self) -> &str {
+        "https://api.kucoin.com"
+    }
+
+    /// Public token and ws endpoints, the ws url is only valid with a fresh token
+    pub async fn bullet_public(&self) -> Result<BulletData, reqwest::Error> {
+        let client = reqwest::Client::new();
+        let url = format!("{}/api/v1/bullet-public", self.rest_url());
+
+        let resp = client.post(&url).send().await?;
+        let data = resp.json::<ApiResponse<BulletData>>().await?;
+        Ok(data.data)
+    }
+
+    /// Top 100 levels per side, the full book endpoint requires an authenticated request
+    pub async fn get_level2_100(&self, symbol: &str) -> Result<Level2Snapshot, reqwest::Error> {
+        let client = reqwest::Client::new();
+        let url = format!("{}/api/v1/market/orderbook/level2_100", self.rest_url());
+
+        let resp = client.get(&url).query(&[("symbol", symbol)]).send().await?;
+        let data = resp.json::<ApiResponse<Level2Snapshot>>().await?;
+        Ok(data.data)
+    }
+}
+
+/// `level2` topic of symbols, e.g. `BTC-USDT`
+pub fn level2_topic(symbols: &[&str]) -> String {
+    format!("/market/level2:{}", symbols.join(","))
+}
+
+pub fn subscribe(symbols: &[&str]) -> String {
+    request("subscribe", symbols)
+}
+
+pub fn unsubscribe(symbols: &[&str]) -> String {
+    request("unsubscribe", symbols)
+}
+
+/// Sent every `pingInterval` of the instance server, the connection is closed otherwise
+pub fn ping() -> String {
+    serde_json::json!({ "id": uuid::Uuid::new_v4().to_string(), "type": "ping" })
+        .to_string()
+}
+
+fn request(kind: &str, symbols: &[&str]) -> String {
+    serde_json::json!({
+        "id": uuid::Uuid::new_v4().to_string(),
+        "type": kind,
+        "topic": level2_topic(symbols),
+        "response": true,
+    })
+    .to_string()
+}
@@ -0,0 +1,118 @@
+use super::api::Kucoin;
+use super::types::Level2Seq;
+use crate::l2_book::tokio::{Book as AsyncBook, SnapshotFetcher};
+use crate::l2_book::{BookSequencer, Order, Sequence};
+use std::time::Duration;
+
+/// Levels per side of the public `level2_100` snapshot
+pub const SNAPSHOT_DEPTH: usize = 100;
+
+/// Each message starts right after the previous `sequenceEnd`.
+/// The first message may overlap the snapshot, its changes are then filtered one by one.
+struct KucoinBookSequencer;
+
+impl BookSequencer<Level2Seq> for KucoinBookSequencer {
+    fn is_first_event(&self, cur_seq: Sequence, update: &Order<Level2Seq>) -> bool {
+        update.o.sequence_start <= cur_seq.val() + 1 && cur_seq.val() < update.o.sequence_end
+    }
+
+    fn is_stale(&self, cur_seq: Sequence, update: &Order<Level2Seq>) -> bool {
+        cur_seq.val() + 1 < update.o.sequence_start
+    }
+
+    fn is_next(&self, cur_seq: Sequence, update: &Order<Level2Seq>) -> bool {
+        cur_seq.val() + 1 == update.o.sequence_start
+    }
+
+    fn skip_applied(&self, cur_seq: Sequence, update: &mut Order<Level2Seq>) {
+        let cur = cur_seq.val();
+
+        let mut seqs = update.o.bid_seqs.iter();
+        update.bids.retain(|_| seqs.next().is_some_and(|&seq| seq > cur));
+        update.o.bid_seqs.retain(|&seq| seq > cur);
+
+        let mut seqs = update.o.ask_seqs.iter();
+        update.asks.retain(|_| seqs.next().is_some_and(|&seq| seq > cur));
+        update.o.ask_seqs.retain(|&seq| seq > cur);
+    }
+}
+
+pub struct KucoinSnapshotFetcher {
+    api: Kucoin,
+}
+
+impl SnapshotFetcher<Level2Seq> for KucoinSnapshotFetcher {
+    type Error = reqwest::Error;
+
+    async fn fetch_snapshot(&self, symbol: &str) -> Result<Order<Level2Seq>, Self::Error> {
+        Ok(self.api.get_level2_100(symbol).await?.into())
+    }
+}
+
+pub struct Book;
+
+impl Book {
+    /// Spot book, connect to the url from [`bullet_public`](super::api::Kucoin::bullet_public).
+    /// Snapshots only cover the top [`SNAPSHOT_DEPTH`] levels per side, a larger `depth` is clamped
+    /// since deeper levels would only be known from increments.
+    pub fn new_kucoin(symbol: impl Into<String>, depth: usize, interval: Duration) -> AsyncBook<Level2Seq> {
+        AsyncBook::new(
+            symbol.into(),
+            KucoinBookSequencer,
+            KucoinSnapshotFetcher { api: Kucoin },
+            depth.min(SNAPSHOT_DEPTH),
+            interval,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::KucoinBookSequencer;
+    use crate::kucoin::types::Level2Seq;
+    use crate::l2_book::{BookAction, BookFsm, Order, Price, PriceSize, Sequence, Size};
+
+    fn level(p: f64, s: f64) -> PriceSize {
+        PriceSize(Price::from(p), Size::from(s))
+    }
+
+    #[test]
+    fn skips_changes_in_snapshot() {
+        let mut fsm = BookFsm::new(KucoinBookSequencer);
+
+        let snapshot = Order {
+            id: Sequence(10),
+            bids: vec![level(100.0, 1.0)],
+            asks: vec![level(101.0, 1.0)],
+            is_snapshot: true,
+            ts_ms: 0,
+            o: Level2Seq {
+                sequence_start: 10,
+                sequence_end: 10,
+                bid_seqs: vec![],
+                ask_seqs: vec![],
+            },
+        };
+        assert_eq!(BookAction::Ok, fsm.update(snapshot));
+
+        // Changes 9 and 10 are already in the snapshot
+        let update = Order {
+            id: Sequence(12),
+            bids: vec![level(100.0, 0.0), level(99.0, 2.0)],
+            asks: vec![level(101.0, 0.0), level(102.0, 3.0)],
+            is_snapshot: false,
+            ts_ms: 0,
+            o: Level2Seq {
+                sequence_start: 9,
+                sequence_end: 12,
+                bid_seqs: vec![9, 11],
+                ask_seqs: vec![10, 12],
+            },
+        };
+        assert_eq!(BookAction::Ok, fsm.update(update));
+
+        let snapshot = fsm.snapshot(10);
+        assert_eq!(snapshot.bids, vec![(Price::from(100.0), Size::from(1.0)), (Price::from(99.0), Size::from(2.0))]);
+        assert_eq!(snapshot.asks, vec![(Price::from(101.0), Size::from(1.0)), (Price::from(102.0), Size::from(3.0))]);
+    }
+}
@@ -0,0 +1,180 @@
+use crate::l2_book;
+use crate::l2_book::types::u64_from_str;
+use serde::Deserialize;
+
+/// REST envelope, `code` is `200000` on success
+#[derive(Deserialize, Debug)]
+pub struct ApiResponse<T> {
+    pub code: String,
+
+    pub data: T,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BulletData {
+    pub token: String,
+
+    #[serde(rename = "instanceServers")]
+    pub instance_servers: Vec<InstanceServer>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct InstanceServer {
+    pub endpoint: String,
+
+    /// Milliseconds between client pings
+    #[serde(rename = "pingInterval")]
+    pub ping_interval: u64,
+
+    #[serde(rename = "pingTimeout")]
+    pub ping_timeout: u64,
+}
+
+impl BulletData {
+    /// Url of the first instance server, with the token and a new `connectId`
+    pub fn ws_url(&self) -> Option<String> {
+        let server = self.instance_servers.first()?;
+
+        Some(format!(
+            "{}?token={}&connectId={}",
+            server.endpoint,
+            self.token,
+            uuid::Uuid::new_v4()
+        ))
+    }
+}
+
+/// `GET /api/v1/market/orderbook/level2_100`
+#[derive(Deserialize, Debug)]
+pub struct Level2Snapshot {
+    pub time: u64,
+
+    #[serde(with = "u64_from_str")]
+    pub sequence: u64,
+
+    pub bids: Vec<l2_book::PriceSize>,
+
+    pub asks: Vec<l2_book::PriceSize>,
+}
+
+/// `trade.l2update` push of the `/market/level2` topic
+#[derive(Deserialize, Debug)]
+pub struct Level2Message<'a> {
+    #[serde(borrow)]
+    pub topic: std::borrow::Cow<'a, str>,
+
+    #[serde(borrow)]
+    pub subject: std::borrow::Cow<'a, str>,
+
+    pub data: Level2Data,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Level2Data {
+    pub changes: Changes,
+
+    #[serde(rename = "sequenceStart")]
+    pub sequence_start: u64,
+
+    #[serde(rename = "sequenceEnd")]
+    pub sequence_end: u64,
+
+    pub symbol: String,
+
+    pub time: u64,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Changes {
+    pub asks: Vec<Change>,
+
+    pub bids: Vec<Change>,
+}
+
+/// `[price, size, sequence]`, a zero price only moves the sequence forward
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Change(pub l2_book::Price, pub l2_book::Size, #[serde(with = "u64_from_str")] pub u64);
+
+/// Message sequence range along with the sequence of each change, in the order of the levels
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Level2Seq {
+    pub sequence_start: u64,
+    pub sequence_end: u64,
+    pub bid_seqs: Vec<u64>,
+    pub ask_seqs: Vec<u64>,
+}
+
+impl From<Level2Message<'_>> for l2_book::Order<Level2Seq> {
+    fn from(val: Level2Message<'_>) -> Self {
+        let data = val.data;
+        let split = |changes: Vec<Change>| -> (Vec<l2_book::PriceSize>, Vec<u64>) {
+            changes
+                .into_iter()
+                .filter(|c| c.0 != l2_book::Price(0))
+                .map(|Change(p, s, seq)| (l2_book::PriceSize(p, s), seq))
+                .unzip()
+        };
+
+        let (bids, bid_seqs) = split(data.changes.bids);
+        let (asks, ask_seqs) = split(data.changes.asks);
+
+        l2_book::Order {
+            id: l2_book::Sequence(data.sequence_end),
+            bids,
+            asks,
+            is_snapshot: false,
+            ts_ms: data.time,
+            o: Level2Seq {
+                sequence_start: data.sequence_start,
+                sequence_end: data.sequence_end,
+                bid_seqs,
+                ask_seqs,
+            },
+        }
+    }
+}
+
+impl From<Level2Snapshot> for l2_book::Order<Level2Seq> {
+    fn from(val: Level2Snapshot) -> Self {
+        l2_book::Order {
+            id: l2_book::Sequence(val.sequence),
+            bids: val.bids,
+            asks: val.asks,
+            is_snapshot: true,
+            ts_ms: val.time,
+            o: Level2Seq {
+                sequence_start: val.sequence,
+                sequence_end: val.sequence,
+                bid_seqs: vec![],
+                ask_seqs: vec![],
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ApiResponse, BulletData, Level2Message};
+    use crate::l2_book::{Order, Price, PriceSize, Size};
+
+    #[test]
+    fn deserialize_update() {
+        let d = r#"{"type":"message","topic":"/market/level2:BTC-USDT","subject":"trade.l2update","data":{"changes":{"asks":[["18906","0.00331","14103845"],["0","0","14103846"]],"bids":[["18891.9","0.15688","14103844"]]},"sequenceEnd":14103846,"sequenceStart":14103844,"symbol":"BTC-USDT","time":1663747970273}}"#;
+        let msg: Level2Message = serde_json::from_str(d).unwrap();
+
+        let order: Order<_> = msg.into();
+        assert_eq!(order.id.val(), 14103846);
+        assert_eq!(order.asks, vec![PriceSize(Price::from(18906.0), Size::from(0.00331))]);
+        assert_eq!(order.o.ask_seqs, vec![14103845]);
+        assert_eq!(order.o.bid_seqs, vec![14103844]);
+    }
+
+    #[test]
+    fn bullet_ws_url() {
+        let d = r#"{"code":"200000","data":{"token":"abc","instanceServers":[{"endpoint":"wss://ws-api-spot.kucoin.com/","encrypt":true,"protocol":"websocket","pingInterval":18000,"pingTimeout":10000}]}}"#;
+        let msg: ApiResponse<BulletData> = serde_json::from_str(d).unwrap();
+
+        let url = msg.data.ws_url().unwrap();
+        assert!(url.starts_with("wss://ws-api-spot.kucoin.com/?token=abc&connectId="));
+    }
+}
@@ -7,6 +7,83 @@ pub trait BookSequencer<O> {
     fn is_first_event(&self, cur_seq: Sequence, update: &Order<O>) -> bool;
     fn is_stale(&self, cur_seq: Sequence, update: &Order<O>) -> bool;
     fn is_next(&self, cur_seq: Sequence, update: &Order<O>) -> bool;
+
+    /// Updates state kept alongside the book, e.g. what a checksum needs besides prices and
+    /// sizes. Called with every applied order, before [`is_valid`](Self::is_valid).
+    fn on_apply(&mut self, _update: &Order<O>) {}
+
+    /// Checks the book once `update` is applied, e.g. against a venue checksum.
+    /// An invalid book is resynced the same way as a sequence gap.
+    fn is_valid(&self, _book: &Levels<'_>, _update: &Order<O>) -> bool {
+        true
+    }
+
+    /// Levels kept per side, for venues that don't send deletes for levels
+    /// pushed beyond the subscribed depth.
+    fn depth_limit(&self) -> Option<usize> {
+        None
+    }
+
+    /// How to resolve a crossed book left by an update
+    fn uncross_policy(&self) -> UncrossPolicy {
+        UncrossPolicy::Keep
+    }
+
+    /// Every update replaces the whole book, for venues that only push full snapshots
+    fn is_snapshot_only(&self) -> bool {
+        false
+    }
+
+    /// Removes the changes of the first event already contained in the snapshot,
+    /// for venues sequencing each change rather than each message
+    fn skip_applied(&self, _cur_seq: Sequence, _update: &mut Order<O>) {}
+}
+
+/// Sequencer for feeds where each message is a complete book, nothing is sequenced
+pub struct SnapshotOnly;
+
+impl<O> BookSequencer<O> for SnapshotOnly {
+    fn is_first_event(&self, _cur_seq: Sequence, _update: &Order<O>) -> bool {
+        true
+    }
+
+    fn is_stale(&self, _cur_seq: Sequence, _update: &Order<O>) -> bool {
+        false
+    }
+
+    fn is_next(&self, _cur_seq: Sequence, _update: &Order<O>) -> bool {
+        true
+    }
+
+    fn is_snapshot_only(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum UncrossPolicy {
+    /// Leave crossed levels in the book
+    #[default]
+    Keep,
+    /// Remove the crossed levels the latest update did not touch,
+    /// or both sides when the update touched both or neither of them
+    DropOlder,
+}
+
+/// Read-only view of the book levels, best price first
+pub struct Levels<'a> {
+    asks: &'a BTreeMap<Price, Size>,
+    bids: &'a BTreeMap<Reverse<Price>, Size>,
+}
+
+impl<'a> Levels<'a> {
+    pub fn asks(&self) -> impl Iterator<Item = (Price, Size)> + 'a {
+        self.asks.iter().map(|(&p, &s)| (p, s))
+    }
+
+    pub fn bids(&self) -> impl Iterator<Item = (Price, Size)> + 'a {
+        self.bids.iter().map(|(Reverse(p), &s)| (*p, s))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -42,6 +119,24 @@ impl BookSnapshot {
 
         Price((best_bid + best_ask) / 2)
     }
+
+    /// Converts inverse contract sizes to base asset, where one contract is worth `contract_size` USD
+    pub fn to_base(&self, contract_size: f64) -> BookSnapshot {
+        self.map_sizes(|p, s| Size::from(f64::from(s) * contract_size / f64::from(p)))
+    }
+
+    /// Converts inverse contract sizes to USD notional, where one contract is worth `contract_size` USD
+    pub fn to_usd(&self, contract_size: f64) -> BookSnapshot {
+        self.map_sizes(|_, s| Size::from(f64::from(s) * contract_size))
+    }
+
+    fn map_sizes(&self, f: impl Fn(Price, Size) -> Size) -> BookSnapshot {
+        BookSnapshot {
+            asks: self.asks.iter().map(|&(p, s)| (p, f(p, s))).collect(),
+            bids: self.bids.iter().map(|&(p, s)| (p, f(p, s))).collect(),
+            ts_ms: self.ts_ms,
+        }
+    }
 }
 
 pub struct BookFsm<O, S: BookSequencer<O>> {
@@ -78,30 +173,61 @@ where
         }
     }
 
-    pub fn update(&mut self, order: Order<O>) -> BookAction {
+    pub fn levels(&self) -> Levels<'_> {
+        Levels {
+            asks: &self.asks,
+            bids: &self.bids,
+        }
+    }
+
+    /// Whether a snapshot has been applied and the book is being kept in sync
+    pub fn is_synced(&self) -> bool {
+        matches!(self.state, BookState::Synchronizing | BookState::Processing)
+    }
+
+    /// Drops all levels and buffered orders, the next update asks for a snapshot again
+    pub fn clear(&mut self) {
+        self.state = BookState::Init;
+        self.asks.clear();
+        self.bids.clear();
+        self.buffer.clear();
+        self.cur_sequence = Sequence(0);
+        self.ts_ms = 0;
+    }
+
+    pub fn update(&mut self, mut order: Order<O>) -> BookAction {
+        order.is_snapshot |= self.sequencer.is_snapshot_only();
         self.process_order(order)
     }
 
-    fn process_order(&mut self, order: Order<O>) -> BookAction {
+    fn process_order(&mut self, mut order: Order<O>) -> BookAction {
+        // A snapshot always replaces the book, whether it was fetched or arrived on the stream
+        if order.is_snapshot {
+            if !self.apply_order(&order) {
+                return self.reset();
+            }
+
+            self.state = BookState::Synchronizing;
+            return self.drain_buffer();
+        }
+
         match self.state {
             BookState::Init => {
                 self.state = BookState::WaitingForSnapshot;
                 BookAction::RetrieveSnapshot
             }
             BookState::WaitingForSnapshot => {
-                if order.is_snapshot {
-                    self.apply_order(&order);
-                    self.state = BookState::Synchronizing;
-                    self.drain_buffer()
-                } else {
-                    // Buffer until we get a snapshot
-                    self.buffer.push_back(order);
-                    BookAction::Ok
-                }
+                // Buffer until we get a snapshot
+                self.buffer.push_back(order);
+                BookAction::Ok
             }
             BookState::Synchronizing => {
                 if self.sequencer.is_first_event(self.cur_sequence, &order) {
-                    self.apply_order(&order);
+                    self.sequencer.skip_applied(self.cur_sequence, &mut order);
+                    if !self.apply_order(&order) {
+                        return self.reset();
+                    }
+
                     self.state = BookState::Processing;
                     return BookAction::Ok;
                 }
@@ -113,11 +239,8 @@ where
                 }
             }
             BookState::Processing => match self.sequencer.is_next(self.cur_sequence, &order) {
-                true => {
-                    self.apply_order(&order);
-                    BookAction::Ok
-                }
-                false => self.reset(),
+                true if self.apply_order(&order) => BookAction::Ok,
+                _ => self.reset(),
             },
         }
     }
@@ -140,7 +263,8 @@ where
         BookAction::RetrieveSnapshot
     }
 
-    fn apply_order(&mut self, order: &Order<O>) {
+    /// Applies the order and returns whether the resulting book is valid
+    fn apply_order(&mut self, order: &Order<O>) -> bool {
         self.cur_sequence = order.id;
         self.ts_ms = order.ts_ms;
 
@@ -162,6 +286,46 @@ where
                 _ => self.asks.insert(pxsz.price(), pxsz.size()),
             };
         }
+
+        if self.sequencer.uncross_policy() == UncrossPolicy::DropOlder {
+            self.drop_older_crossed(order);
+        }
+
+        if let Some(depth) = self.sequencer.depth_limit() {
+            while self.asks.len() > depth {
+                self.asks.pop_last();
+            }
+
+            while self.bids.len() > depth {
+                self.bids.pop_last();
+            }
+        }
+
+        self.sequencer.on_apply(order);
+        self.sequencer.is_valid(&self.levels(), order)
+    }
+
+    fn drop_older_crossed(&mut self, order: &Order<O>) {
+        loop {
+            let (Some((&Reverse(bid), _)), Some((&ask, _))) = (self.bids.first_key_value(), self.asks.first_key_value()) else {
+                return;
+            };
+
+            if bid < ask {
+                return;
+            }
+
+            let bid_fresh = order.bids.iter().any(|l| l.price() == bid);
+            let ask_fresh = order.asks.iter().any(|l| l.price() == ask);
+
+            if !bid_fresh || ask_fresh {
+                self.bids.remove(&Reverse(bid));
+            }
+
+            if bid_fresh || !ask_fresh {
+                self.asks.remove(&ask);
+            }
+        }
     }
 }
 
@@ -169,7 +333,7 @@ where
 mod test {
 
     use super::*;
-    use crate::l2_book::types::{Order, Sequence};
+    use crate::l2_book::types::{Order, PriceSize, Sequence};
     use std::time::{SystemTime, UNIX_EPOCH};
 
     #[test]
@@ -215,6 +379,68 @@ mod test {
         assert_eq!(BookAction::RetrieveSnapshot, fsm.update(inc(10, 13, 14)))
     }
 
+    #[test]
+    fn test_stream_snapshot() {
+        let mut fsm = BookFsm::new(TestSequencer);
+
+        // Snapshot from the stream is applied without asking for one
+        assert_eq!(BookAction::Ok, fsm.update(snap(0, 0, 7)));
+        assert_eq!(BookState::Synchronizing, fsm.state);
+
+        assert_eq!(BookAction::Ok, fsm.update(inc(5, 7, 10)));
+        assert_eq!(BookState::Processing, fsm.state);
+
+        // A fresh snapshot resets the book
+        assert_eq!(BookAction::Ok, fsm.update(snap(0, 0, 20)));
+        assert_eq!(BookState::Synchronizing, fsm.state);
+        assert_eq!(Sequence(20), fsm.cur_sequence);
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut fsm = BookFsm::new(TestSequencer);
+        assert_eq!(BookAction::Ok, fsm.update(snap(0, 0, 7)));
+        assert_eq!(BookAction::Ok, fsm.update(inc(5, 7, 10)));
+
+        // Connection dropped, the book starts over
+        fsm.clear();
+        assert!(!fsm.is_synced());
+        assert_eq!(BookAction::RetrieveSnapshot, fsm.update(inc(20, 21, 22)));
+    }
+
+    #[test]
+    fn test_snapshot_only() {
+        let mut fsm = BookFsm::new(SnapshotOnly);
+
+        let mut first = inc(0, 0, 1);
+        first.bids = vec![PriceSize(Price::from(10.0), Size::from(1.0))];
+        assert_eq!(BookAction::Ok, fsm.update(first));
+        assert!(fsm.is_synced());
+
+        // No sequencing, the next message replaces the book
+        let mut second = inc(0, 0, 0);
+        second.bids = vec![PriceSize(Price::from(9.0), Size::from(2.0))];
+        assert_eq!(BookAction::Ok, fsm.update(second));
+        assert_eq!(fsm.snapshot(10).bids, vec![(Price::from(9.0), Size::from(2.0))]);
+    }
+
+    #[test]
+    fn test_contract_notional() {
+        let snapshot = BookSnapshot {
+            asks: vec![(Price::from(50_000.0), Size::from(10.0))],
+            bids: vec![(Price::from(40_000.0), Size::from(4.0))],
+            ts_ms: 0,
+        };
+
+        let usd = snapshot.to_usd(100.0);
+        assert_eq!(usd.asks[0].1, Size::from(1_000.0));
+        assert_eq!(usd.bids[0].1, Size::from(400.0));
+
+        let base = snapshot.to_base(100.0);
+        assert_eq!(base.asks[0].1, Size::from(0.02));
+        assert_eq!(base.bids[0].1, Size::from(0.01));
+    }
+
     struct TestOrder {
         pub prev_seq: Sequence,
         pub start_seq: Sequence,
@@ -0,0 +1,161 @@
+use super::fsm::BookSnapshot;
+use super::queue::Queue;
+use super::types::{Price, PriceSize, Size, ZERO_SIZE};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    Bid,
+    Ask,
+}
+
+/// Orders resting at one price in arrival order, with their total size
+struct Level {
+    size: Size,
+    orders: Queue<u64, Size>,
+}
+
+/// Order-by-order book, each price level is a FIFO of order ids.
+/// Changes are reported as aggregated levels so they can be fed to a [`BookFsm`](super::BookFsm).
+#[derive(Default)]
+pub struct OrderBook {
+    asks: BTreeMap<Price, Level>,
+    bids: BTreeMap<Price, Level>,
+    orders: HashMap<u64, (Side, Price)>,
+}
+
+impl OrderBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.asks.clear();
+        self.bids.clear();
+        self.orders.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    /// Adds an order or changes an existing one. A size change keeps the order's
+    /// queue position, a price or side change moves it to the back of the new level.
+    /// Returns the new size of every level touched.
+    pub fn upsert(&mut self, id: u64, side: Side, price: Price, size: Size) -> Vec<(Side, PriceSize)> {
+        let mut changes = Vec::with_capacity(2);
+
+        match self.orders.get(&id) {
+            Some(&(s, p)) if s == side && p == price => {
+                let level = self.side_mut(side).get_mut(&price).unwrap();
+                let order = level.orders.get_mut(&id).unwrap();
+                level.size = Size(level.size.0 - order.0 + size.0);
+                *order = size;
+                changes.push((side, PriceSize(price, level.size)));
+                return changes;
+            }
+            Some(_) => changes.extend(self.remove(id)),
+            None => {}
+        }
+
+        let level = self.side_mut(side).entry(price).or_insert_with(|| Level {
+            size: ZERO_SIZE,
+            orders: Queue::new(),
+        });
+        level.orders.push_back(id, size);
+        level.size = Size(level.size.0 + size.0);
+        changes.push((side, PriceSize(price, level.size)));
+
+        self.orders.insert(id, (side, price));
+        changes
+    }
+
+    /// Removes an order, returning the new size of its level
+    pub fn remove(&mut self, id: u64) -> Option<(Side, PriceSize)> {
+        let (side, price) = self.orders.remove(&id)?;
+        let levels = self.side_mut(side);
+        let level = levels.get_mut(&price)?;
+
+        let size = level.orders.get(&id).copied().unwrap_or(ZERO_SIZE);
+        level.orders.remove_key(id);
+        level.size = Size(level.size.0 - size.0);
+
+        let left = level.size;
+        if level.orders.is_empty() {
+            levels.remove(&price);
+        }
+
+        Some((side, PriceSize(price, left)))
+    }
+
+    /// Orders of one side as `(id, price, size)`, best price first and in queue order within a level
+    pub fn orders(&self, side: Side) -> Box<dyn Iterator<Item = (u64, Price, Size)> + '_> {
+        match side {
+            Side::Bid => Box::new(self.bids.iter().rev().flat_map(level_orders)),
+            Side::Ask => Box::new(self.asks.iter().flat_map(level_orders)),
+        }
+    }
+
+    /// Aggregated view of the book
+    pub fn snapshot(&self, depth: usize, ts_ms: u64) -> BookSnapshot {
+        BookSnapshot {
+            asks: self.asks.iter().take(depth).map(|(&p, l)| (p, l.size)).collect(),
+            bids: self.bids.iter().rev().take(depth).map(|(&p, l)| (p, l.size)).collect(),
+            ts_ms,
+        }
+    }
+
+    fn side_mut(&mut self, side: Side) -> &mut BTreeMap<Price, Level> {
+        match side {
+            Side::Bid => &mut self.bids,
+            Side::Ask => &mut self.asks,
+        }
+    }
+}
+
+fn level_orders<'a>((&price, level): (&'a Price, &'a Level)) -> impl Iterator<Item = (u64, Price, Size)> + 'a {
+    level.orders.iter().map(move |(&id, &size)| (id, price, size))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{OrderBook, Side};
+    use crate::l2_book::{Price, PriceSize, Size};
+
+    #[test]
+    fn aggregates_orders() {
+        let mut book = OrderBook::new();
+        let p = Price::from(100.0);
+
+        book.upsert(1, Side::Bid, p, Size::from(1.0));
+        book.upsert(2, Side::Bid, p, Size::from(2.0));
+        book.upsert(3, Side::Ask, Price::from(101.0), Size::from(0.5));
+
+        // Size change keeps the queue position
+        let changes = book.upsert(1, Side::Bid, p, Size::from(0.5));
+        assert_eq!(changes, vec![(Side::Bid, PriceSize(p, Size::from(2.5)))]);
+        let ids: Vec<_> = book.orders(Side::Bid).map(|(id, _, _)| id).collect();
+        assert_eq!(ids, vec![1, 2]);
+
+        // Price change moves the order
+        let changes = book.upsert(2, Side::Bid, Price::from(99.0), Size::from(2.0));
+        assert_eq!(
+            changes,
+            vec![
+                (Side::Bid, PriceSize(p, Size::from(0.5))),
+                (Side::Bid, PriceSize(Price::from(99.0), Size::from(2.0)))
+            ]
+        );
+
+        assert_eq!(book.remove(1), Some((Side::Bid, PriceSize(p, Size::from(0.0)))));
+        assert_eq!(book.remove(1), None);
+
+        let snapshot = book.snapshot(10, 0);
+        assert_eq!(snapshot.bids, vec![(Price::from(99.0), Size::from(2.0))]);
+        assert_eq!(snapshot.asks, vec![(Price::from(101.0), Size::from(0.5))]);
+    }
+}
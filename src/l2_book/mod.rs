@@ -1,8 +1,9 @@
 pub mod fsm;
+pub mod l3;
 pub mod queue;
 pub mod tokio;
 pub mod types;
 
-pub use fsm::{BookAction, BookFsm, BookSequencer};
+pub use fsm::{BookAction, BookFsm, BookSequencer, Levels, SnapshotOnly, UncrossPolicy};
 pub use queue::Queue;
 pub use types::{Order, Price, PriceSize, Sequence, Size};
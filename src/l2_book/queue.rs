@@ -51,6 +51,21 @@ impl<K: Eq + Hash + Clone, V> Queue<K, V> {
         Some(&mut node.value)
     }
 
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.keys.contains_key(key)
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let id = *self.keys.get(key)?;
+        Some(&self.backing.get(id)?.value)
+    }
+
+    /// Mutable access to the value of `key`, keeping its position in the queue
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let id = *self.keys.get(key)?;
+        Some(&mut self.backing.get_mut(id)?.value)
+    }
+
     pub fn remove_key(&mut self, key: K) -> bool {
         match self.keys.remove(&key) {
             Some(key) => {
@@ -60,14 +75,14 @@ impl<K: Eq + Hash + Clone, V> Queue<K, V> {
                     let next_node = self.backing.get_mut(nid).unwrap();
                     next_node.prev = node.prev;
                 } else {
-                    self.tail = None
+                    self.tail = node.prev
                 }
 
                 if let Some(pid) = node.prev {
                     let prev_node = self.backing.get_mut(pid).unwrap();
                     prev_node.next = node.next;
                 } else {
-                    self.head = None
+                    self.head = node.next
                 }
 
                 true
@@ -198,4 +213,21 @@ mod test {
         assert_eq!(list.pop_front(), Some("c"));
         assert_eq!(list.pop_front(), Some("d"));
     }
+
+    #[test]
+    fn remove_head_and_tail() {
+        let mut list = Queue::new();
+
+        list.push_back("a", 1);
+        list.push_back("b", 2);
+        list.push_back("c", 3);
+
+        assert!(list.remove_key("a"));
+        assert!(list.remove_key("c"));
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![(&"b", &2)]);
+
+        list.push_back("d", 4);
+        *list.get_mut(&"b").unwrap() = 5;
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![(&"b", &5), (&"d", &4)]);
+    }
 }
@@ -1,11 +1,14 @@
 use crate::l2_book::fsm::BookSnapshot;
 
-use super::fsm::{BookAction, BookFsm, BookSequencer};
+use super::fsm::{BookAction, BookFsm, BookSequencer, SnapshotOnly};
 use super::types::Order;
+use std::collections::VecDeque;
 use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
 use tokio::sync::mpsc;
 use tokio::sync::oneshot;
-use tokio::time::{Duration, Instant};
+use tokio::time::{Duration, Instant, sleep_until};
 
 pub trait SnapshotFetcher<O> {
     type Error: std::fmt::Debug + Send;
@@ -13,9 +16,67 @@ pub trait SnapshotFetcher<O> {
     fn fetch_snapshot(&self, symbol: &str) -> impl Future<Output = Result<Order<O>, Self::Error>> + Send;
 }
 
+/// Placeholder fetcher for books whose snapshots are delivered on the stream
+pub struct NoSnapshotFetcher;
+
+impl<O> SnapshotFetcher<O> for NoSnapshotFetcher {
+    type Error = &'static str;
+
+    async fn fetch_snapshot(&self, _symbol: &str) -> Result<Order<O>, Self::Error> {
+        Err("snapshot is delivered on the stream")
+    }
+}
+
+/// Request weight budget shared by the fetchers of many books, so a mass reset such as every
+/// book of a dropped connection doesn't send all of its snapshot requests at once
+#[derive(Clone)]
+pub struct SnapshotLimiter {
+    capacity: u32,
+    window: Duration,
+    spent: Arc<Mutex<VecDeque<(Instant, u32)>>>,
+}
+
+impl SnapshotLimiter {
+    /// At most `capacity` weight is spent within any `window`
+    pub fn new(capacity: u32, window: Duration) -> Self {
+        Self {
+            capacity,
+            window,
+            spent: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+
+    /// Waits until `weight` fits in the budget, it is given back once `window` elapsed
+    pub async fn acquire(&self, weight: u32) {
+        let weight = weight.min(self.capacity);
+
+        loop {
+            let retry_at = {
+                let mut spent = self.spent.lock().unwrap();
+                let now = Instant::now();
+
+                while spent.front().is_some_and(|(at, _)| *at + self.window <= now) {
+                    spent.pop_front();
+                }
+
+                if spent.iter().map(|(_, w)| w).sum::<u32>() + weight <= self.capacity {
+                    spent.push_back((now, weight));
+                    return;
+                }
+
+                spent[0].0 + self.window
+            };
+
+            sleep_until(retry_at).await;
+        }
+    }
+}
+
 pub enum BookMessage<O> {
     Update(Order<O>),
     RequestSnapshot(oneshot::Sender<BookSnapshot>),
+    /// Stream was interrupted, drop the book and wait for a new snapshot
+    Reset,
 }
 
 pub struct BookProcessor<O, S, F>
@@ -24,7 +85,8 @@ where
     F: SnapshotFetcher<O>,
 {
     fsm: BookFsm<O, S>,
-    fetcher: F,
+    fetcher: Option<F>,
+    resync: Arc<Notify>,
     symbol: String,
     pub_interval: Duration,
     pub_at: Option<Instant>,
@@ -40,10 +102,12 @@ where
     S: BookSequencer<O> + Send + 'static,
     F: SnapshotFetcher<O> + Send + 'static,
 {
+    /// Without a `fetcher`, snapshots must come from the stream and a lost sync is
+    /// signalled through [`BookProcessor::resync`] instead.
     pub fn new(
         symbol: String,
         sequencer: S,
-        fetcher: F,
+        fetcher: Option<F>,
         depth: usize,
         pub_interval: Duration,
         book_msg_rx: mpsc::Receiver<BookMessage<O>>,
@@ -52,6 +116,7 @@ where
         Self {
             fsm: BookFsm::new(sequencer),
             fetcher,
+            resync: Arc::new(Notify::new()),
             symbol,
             pub_interval,
             pub_at: None,
@@ -62,6 +127,10 @@ where
         }
     }
 
+    pub fn resync(&self) -> Arc<Notify> {
+        self.resync.clone()
+    }
+
     pub async fn run(mut self) {
         while let Some(msg) = self.book_msg_rx.recv().await {
             match msg {
@@ -69,23 +138,39 @@ where
                 BookMessage::RequestSnapshot(tx) => {
                     let _ = tx.send(self.fsm.snapshot(self.depth));
                 }
+                BookMessage::Reset => {
+                    self.fsm.clear();
+                    self.snap_at = None;
+                    self.pub_at = None;
+                }
             }
         }
     }
 
-    async fn on_update(&mut self, mut order: Order<O>) {
-        while let BookAction::RetrieveSnapshot = self.fsm.update(order) {
+    async fn on_update(&mut self, order: Order<O>) {
+        let mut action = self.fsm.update(order);
+
+        while let BookAction::RetrieveSnapshot = action {
             self.snap_at = None;
 
-            match self.fetcher.fetch_snapshot(&self.symbol).await {
-                Ok(snapshot) => order = snapshot,
+            let Some(fetcher) = &self.fetcher else {
+                // Stream has to deliver a fresh snapshot
+                self.resync.notify_one();
+                return;
+            };
+
+            match fetcher.fetch_snapshot(&self.symbol).await {
+                Ok(snapshot) => action = self.fsm.update(snapshot),
                 Err(e) => {
                     // TODO: think of how to handle error
                     eprintln!("failed to fetch: {:?}", e);
                     return;
                 }
             };
+        }
 
+        // Snapshot was either fetched or delivered by the stream
+        if self.snap_at.is_none() && self.fsm.is_synced() {
             self.snap_at = Some(Instant::now());
         }
 
@@ -116,6 +201,7 @@ where
 pub struct Book<O> {
     book_msg_tx: mpsc::Sender<BookMessage<O>>,
     book_pub_rx: mpsc::Receiver<BookSnapshot>,
+    resync: Arc<Notify>,
 }
 
 impl<O> Book<O>
@@ -123,6 +209,27 @@ where
     O: Send + 'static,
 {
     pub fn new<S, F>(symbol: String, sequence: S, fetcher: F, depth: usize, interval: Duration) -> Self
+    where
+        S: BookSequencer<O> + Send + 'static,
+        F: SnapshotFetcher<O> + Send + 'static,
+    {
+        Self::spawn(symbol, sequence, Some(fetcher), depth, interval)
+    }
+
+    /// Book for venues that send their snapshot over the stream, see [`BookWriter::snapshot_requested`]
+    pub fn new_streamed<S>(symbol: String, sequence: S, depth: usize, interval: Duration) -> Self
+    where
+        S: BookSequencer<O> + Send + 'static,
+    {
+        Self::spawn(symbol, sequence, None::<NoSnapshotFetcher>, depth, interval)
+    }
+
+    /// Book for feeds that push the complete book on every message
+    pub fn new_snapshot_only(symbol: String, depth: usize, interval: Duration) -> Self {
+        Self::new_streamed(symbol, SnapshotOnly, depth, interval)
+    }
+
+    fn spawn<S, F>(symbol: String, sequence: S, fetcher: Option<F>, depth: usize, interval: Duration) -> Self
     where
         S: BookSequencer<O> + Send + 'static,
         F: SnapshotFetcher<O> + Send + 'static,
@@ -131,9 +238,14 @@ where
         let (book_pub_tx, book_pub_rx) = mpsc::channel(1000);
 
         let processor = BookProcessor::new(symbol, sequence, fetcher, depth, interval, book_msg_rx, book_pub_tx);
+        let resync = processor.resync();
         tokio::spawn(processor.run());
 
-        Self { book_msg_tx, book_pub_rx }
+        Self {
+            book_msg_tx,
+            book_pub_rx,
+            resync,
+        }
     }
 
     pub async fn recv(&mut self) -> Option<BookSnapshot> {
@@ -143,13 +255,23 @@ where
     pub fn writer(&self) -> BookWriter<O> {
         BookWriter {
             tx: self.book_msg_tx.clone(),
+            resync: self.resync.clone(),
         }
     }
 }
 
-#[derive(Clone)]
 pub struct BookWriter<O> {
     tx: mpsc::Sender<BookMessage<O>>,
+    resync: Arc<Notify>,
+}
+
+impl<O> Clone for BookWriter<O> {
+    fn clone(&self) -> Self {
+        Self {
+            tx: self.tx.clone(),
+            resync: self.resync.clone(),
+        }
+    }
 }
 
 impl<O> BookWriter<O>
@@ -159,4 +281,113 @@ where
     pub async fn update(&self, order: Order<O>) {
         let _ = self.tx.send(BookMessage::Update(order)).await;
     }
+
+    /// Discards the book after the stream lost updates, e.g. on reconnect.
+    /// Nothing is published until a new snapshot is fetched or arrives on the stream.
+    pub async fn reset(&self) {
+        let _ = self.tx.send(BookMessage::Reset).await;
+    }
+
+    /// Resolves once a streamed book lost sync and needs a fresh snapshot from the stream,
+    /// usually by resubscribing.
+    pub async fn snapshot_requested(&self) {
+        self.resync.notified().await
+    }
+}
+
+#[cfg(test)]
+impl<O> BookWriter<O> {
+    /// Writer without a book, its messages come out of the receiver
+    pub(crate) fn channel() -> (Self, mpsc::Receiver<BookMessage<O>>) {
+        let (tx, rx) = mpsc::channel(50);
+        let writer = Self {
+            tx,
+            resync: Arc::new(Notify::new()),
+        };
+
+        (writer, rx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Book, SnapshotFetcher, SnapshotLimiter};
+    use crate::l2_book::{BookSequencer, Order, Sequence};
+    use std::sync::{Arc, Mutex};
+    use tokio::time::{Duration, Instant};
+
+    struct Seq;
+
+    impl BookSequencer<()> for Seq {
+        fn is_first_event(&self, cur_seq: Sequence, update: &Order<()>) -> bool {
+            cur_seq.val() < update.id.val()
+        }
+
+        fn is_stale(&self, _cur_seq: Sequence, _update: &Order<()>) -> bool {
+            false
+        }
+
+        fn is_next(&self, cur_seq: Sequence, update: &Order<()>) -> bool {
+            cur_seq.val() + 1 == update.id.val()
+        }
+    }
+
+    fn order(id: u64, is_snapshot: bool) -> Order<()> {
+        Order {
+            id: Sequence(id),
+            bids: vec![],
+            asks: vec![],
+            is_snapshot,
+            ts_ms: 0,
+            o: (),
+        }
+    }
+
+    /// Records when each snapshot request went out
+    struct Fetcher {
+        limiter: SnapshotLimiter,
+        fetched_at: Arc<Mutex<Vec<Instant>>>,
+    }
+
+    impl SnapshotFetcher<()> for Fetcher {
+        type Error = ();
+
+        async fn fetch_snapshot(&self, _symbol: &str) -> Result<Order<()>, ()> {
+            self.limiter.acquire(1).await;
+            self.fetched_at.lock().unwrap().push(Instant::now());
+            Ok(order(1, true))
+        }
+    }
+
+    #[tokio::test]
+    async fn mass_reset_is_throttled() {
+        let window = Duration::from_millis(100);
+        let limiter = SnapshotLimiter::new(2, window);
+        let fetched_at = Arc::new(Mutex::new(vec![]));
+
+        let books: Vec<_> = (0..5)
+            .map(|i| {
+                let fetcher = Fetcher {
+                    limiter: limiter.clone(),
+                    fetched_at: fetched_at.clone(),
+                };
+                Book::new(i.to_string(), Seq, fetcher, 10, Duration::ZERO)
+            })
+            .collect();
+
+        // Every book asks for a snapshot at once, as after a dropped connection
+        let start = Instant::now();
+        for book in books.iter() {
+            book.writer().reset().await;
+            book.writer().update(order(2, false)).await;
+        }
+
+        tokio::time::sleep(window / 2).await;
+        assert_eq!(fetched_at.lock().unwrap().len(), 2);
+
+        tokio::time::sleep(window * 3).await;
+        let fetched_at = fetched_at.lock().unwrap();
+        assert_eq!(fetched_at.len(), 5);
+        assert!(fetched_at[4] - start >= window * 2);
+    }
 }
@@ -1,3 +1,6 @@
+use serde::{Deserialize, Deserializer};
+use std::fmt;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Price(pub u64);
 impl From<f64> for Price {
@@ -12,6 +15,18 @@ impl From<Price> for f64 {
     }
 }
 
+impl fmt::Display for Price {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_scaled(self.0, f)
+    }
+}
+
+impl<'de> Deserialize<'de> for Price {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        f64_to_u64::deserialize(deserializer).map(Price)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Size(pub u64);
 impl From<f64> for Size {
@@ -26,7 +41,20 @@ impl From<Size> for f64 {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+impl fmt::Display for Size {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_scaled(self.0, f)
+    }
+}
+
+impl<'de> Deserialize<'de> for Size {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        f64_to_u64::deserialize(deserializer).map(Size)
+    }
+}
+
+/// Deserializes from a `[price, size]` pair of strings or numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
 pub struct PriceSize(pub Price, pub Size);
 impl PriceSize {
     pub fn price(&self) -> Price {
@@ -48,6 +76,7 @@ impl Sequence {
     }
 }
 
+#[derive(Debug)]
 pub struct Order<O> {
     pub id: Sequence,
     pub bids: Vec<PriceSize>,
@@ -59,6 +88,67 @@ pub struct Order<O> {
 
 pub const FLOAT_SCALE: f64 = 10_000_000_000.0;
 
+/// Number of decimal digits kept by [`FLOAT_SCALE`]
+pub const SCALE_DIGITS: usize = 10;
+
+const INT_SCALE: u64 = 10_000_000_000;
+
+/// Writes a scaled value as a decimal without trailing zeros, or with exactly
+/// the requested precision, e.g. `{:.2}`, rounding half to even like `f64` does.
+fn fmt_scaled(val: u64, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let val = match f.precision() {
+        Some(p) if p < SCALE_DIGITS => round_half_even(val, 10u64.pow((SCALE_DIGITS - p) as u32)),
+        _ => val,
+    };
+
+    let int = val / INT_SCALE;
+    let frac = format!("{:0width$}", val % INT_SCALE, width = SCALE_DIGITS);
+
+    let frac = match f.precision() {
+        Some(p) if p <= SCALE_DIGITS => frac[..p].to_string(),
+        Some(p) => format!("{:0<width$}", frac, width = p),
+        None => frac.trim_end_matches('0').to_string(),
+    };
+
+    match frac.is_empty() {
+        true => write!(f, "{}", int),
+        false => write!(f, "{}.{}", int, frac),
+    }
+}
+
+fn round_half_even(val: u64, unit: u64) -> u64 {
+    let (q, r) = (val / unit, val % unit);
+    let up = r > unit / 2 || (r == unit / 2 && q % 2 == 1);
+
+    (q + up as u64) * unit
+}
+
+/// Parses a plain decimal string into a scaled value without going through `f64`,
+/// digits beyond [`SCALE_DIGITS`] are truncated.
+pub fn parse_scaled(s: &str) -> Option<u64> {
+    let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+    if int.is_empty() && frac.is_empty() {
+        return None;
+    }
+
+    if !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let int: u64 = match int.is_empty() {
+        true => 0,
+        false => int.parse().ok()?,
+    };
+
+    let frac = frac
+        .bytes()
+        .chain(std::iter::repeat(b'0'))
+        .take(SCALE_DIGITS)
+        .fold(0, |acc, b| acc * 10 + (b - b'0') as u64);
+
+    int.checked_mul(INT_SCALE)?.checked_add(frac)
+}
+
 /// Serializer and Deserializer for converting float to u64
 /// Currently limited to precision of 1e10.
 pub mod f64_to_u64 {
@@ -99,11 +189,104 @@ pub mod f64_to_u64 {
         }
 
         match NumOrStr::deserialize(deserializer)? {
-            NumOrStr::Str(s) => {
-                let f: f64 = s.parse().map_err(de::Error::custom)?;
-                f.to_u64()
-            }
+            NumOrStr::Str(s) => match super::parse_scaled(s) {
+                Some(n) => Ok(n),
+                None => {
+                    let f: f64 = s.parse().map_err(de::Error::custom)?;
+                    f.to_u64()
+                }
+            },
             NumOrStr::Float(f) => f.to_u64(),
         }
     }
 }
+
+/// Parses an RFC 3339 UTC timestamp, e.g. `2023-10-06T17:35:55.440295Z`, into unix milliseconds
+pub fn parse_rfc3339_ms(s: &str) -> Option<u64> {
+    let num = |r: std::ops::Range<usize>| -> Option<i64> { s.get(r)?.parse().ok() };
+
+    let (year, month, day) = (num(0..4)?, num(5..7)?, num(8..10)?);
+    let (hour, min, sec) = (num(11..13)?, num(14..16)?, num(17..19)?);
+
+    let rest = s.get(19..)?;
+    let (frac, offset) = match rest.strip_prefix('.') {
+        Some(r) => r.split_at(r.bytes().take_while(u8::is_ascii_digit).count()),
+        None => ("", rest),
+    };
+
+    let millis: i64 = frac.bytes().chain(std::iter::repeat(b'0')).take(3).fold(0, |acc, b| acc * 10 + (b - b'0') as i64);
+
+    let offset_secs = match offset {
+        "Z" | "z" | "+00:00" => 0,
+        _ => {
+            let sign = match offset.get(0..1)? {
+                "+" => 1,
+                "-" => -1,
+                _ => return None,
+            };
+            sign * (offset.get(1..3)?.parse::<i64>().ok()? * 3600 + offset.get(4..6)?.parse::<i64>().ok()? * 60)
+        }
+    };
+
+    // Days since epoch of a proleptic gregorian date
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    let secs = days * 86_400 + hour * 3600 + min * 60 + sec - offset_secs;
+    u64::try_from(secs * 1000 + millis).ok()
+}
+
+/// Deserializer for integers that may be sent as strings, e.g. `"1597026383085"`
+pub mod u64_from_str {
+    use serde::{Deserialize, Deserializer, de};
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<u64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum NumOrStr<'a> {
+            Str(&'a str),
+            Int(u64),
+        }
+
+        match NumOrStr::deserialize(deserializer)? {
+            NumOrStr::Str(s) => s.parse().map_err(de::Error::custom),
+            NumOrStr::Int(n) => Ok(n),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Price, Size, parse_rfc3339_ms, parse_scaled};
+
+    #[test]
+    fn parse_and_format() {
+        assert_eq!(parse_scaled("0.29"), Some(2_900_000_000));
+        assert_eq!(parse_scaled("3366"), Some(33_660_000_000_000));
+        assert_eq!(parse_scaled(".5"), Some(5_000_000_000));
+        assert_eq!(parse_scaled("1e-5"), None);
+
+        assert_eq!(Price(parse_scaled("3366.10").unwrap()).to_string(), "3366.1");
+        assert_eq!(Size(parse_scaled("8").unwrap()).to_string(), "8");
+        assert_eq!(format!("{:.3}", Price(parse_scaled("0.5").unwrap())), "0.500");
+        assert_eq!(format!("{:.0}", Price(parse_scaled("12.5").unwrap())), "12");
+        assert_eq!(format!("{:.0}", Price(parse_scaled("13.5").unwrap())), "14");
+        assert_eq!(format!("{:.2}", Price(parse_scaled("0.125").unwrap())), "0.12");
+        assert_eq!(format!("{:.2}", Price(parse_scaled("1.999").unwrap())), "2.00");
+    }
+
+    #[test]
+    fn parse_timestamps() {
+        assert_eq!(parse_rfc3339_ms("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(parse_rfc3339_ms("2023-10-06T17:35:55.440295Z"), Some(1_696_613_755_440));
+        assert_eq!(parse_rfc3339_ms("2023-10-06T19:35:55.4+02:00"), Some(1_696_613_755_400));
+        assert_eq!(parse_rfc3339_ms("2023-10-06"), None);
+    }
+}
@@ -1,3 +1,18 @@
 pub mod binance;
+pub mod bitfinex;
+pub mod bitmex;
+pub mod bitstamp;
+pub mod bybit;
+pub mod coinbase;
+pub mod deribit;
+pub mod dydx;
+pub mod feed;
+pub mod htx;
+pub mod hyperliquid;
+pub mod kraken;
+pub mod kucoin;
 pub mod l2_book;
+pub mod mexc;
+pub mod okx;
+pub mod venue;
 pub mod ws;
@@ -0,0 +1,50 @@
+use super::types::DepthSnapshot;
+
+/// MEXC spot v3 API
+pub struct Mexc;
+
+impl Mexc {
+    pub fn rest_url(&self) -> &str {
+        "https://api.mexc.com"
+    }
+
+    pub fn ws_url(&self) -> &str {
+        "wss://wbs-api.mexc.com/ws"
+    }
+
+    pub async fn get_depth(&self, symbol: &str) -> Result<DepthSnapshot, reqwest::Error> {
+        let client = reqwest::Client::new();
+        let url = format!("{}/api/v3/depth", self.rest_url());
+
+        let resp = client
+            .get(&url)
+            .query(&[("symbol", symbol), ("limit", "5000")])
+            .send()
+            .await?;
+
+        resp.json::<DepthSnapshot>().await
+    }
+}
+
+/// Protobuf aggregated depth channel of a symbol, e.g. `BTCUSDT`. `interval` is `10ms` or `100ms`.
+pub fn aggre_depth_channel(symbol: &str, interval: &str) -> String {
+    format!("spot@public.aggre.depth.v3.api.pb@{}@{}", interval, symbol)
+}
+
+pub fn subscribe(channels: &[String]) -> String {
+    request("SUBSCRIPTION", channels)
+}
+
+pub fn unsubscribe(channels: &[String]) -> String {
+    request("UNSUBSCRIPTION", channels)
+}
+
+/// Keeps the connection alive, it is closed after a minute without subscriptions or pings
+pub fn ping() -> String {
+    serde_json::json!({ "method": "PING" }).to_string()
+}
+
+fn request(method: &str, channels: &[String]) -> String {
+    serde_json::json!({ "method": method, "params": channels })
+        .to_string()
+}
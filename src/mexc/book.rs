@@ -0,0 +1,49 @@
+use super::api::Mexc;
+use super::types::DepthVersion;
+use crate::l2_book::tokio::{Book as AsyncBook, SnapshotFetcher};
+use crate::l2_book::{BookSequencer, Order, Sequence};
+use std::time::Duration;
+
+/// Each update starts right after the previous `toVersion`
+struct MexcBookSequencer;
+
+impl BookSequencer<DepthVersion> for MexcBookSequencer {
+    fn is_first_event(&self, cur_seq: Sequence, update: &Order<DepthVersion>) -> bool {
+        update.o.from_version <= cur_seq.val() + 1 && cur_seq.val() < update.o.to_version
+    }
+
+    fn is_stale(&self, cur_seq: Sequence, update: &Order<DepthVersion>) -> bool {
+        cur_seq.val() + 1 < update.o.from_version
+    }
+
+    fn is_next(&self, cur_seq: Sequence, update: &Order<DepthVersion>) -> bool {
+        cur_seq.val() + 1 == update.o.from_version
+    }
+}
+
+pub struct MexcSnapshotFetcher {
+    api: Mexc,
+}
+
+impl SnapshotFetcher<DepthVersion> for MexcSnapshotFetcher {
+    type Error = reqwest::Error;
+
+    async fn fetch_snapshot(&self, symbol: &str) -> Result<Order<DepthVersion>, Self::Error> {
+        Ok(self.api.get_depth(symbol).await?.into())
+    }
+}
+
+pub struct Book;
+
+impl Book {
+    /// Spot book fed with decoded [`PushDataV3ApiWrapper`](super::proto::PushDataV3ApiWrapper) frames
+    pub fn new_mexc(symbol: impl Into<String>, depth: usize, interval: Duration) -> AsyncBook<DepthVersion> {
+        AsyncBook::new(
+            symbol.into(),
+            MexcBookSequencer,
+            MexcSnapshotFetcher { api: Mexc },
+            depth,
+            interval,
+        )
+    }
+}
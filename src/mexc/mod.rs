@@ -0,0 +1,6 @@
+pub mod api;
+pub mod book;
+pub mod proto;
+pub mod types;
+
+pub use book::Book;
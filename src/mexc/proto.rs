@@ -0,0 +1,131 @@
+//! Protobuf messages of the spot v3 market streams, see
+//! <https://github.com/mexcdevelop/websocket-proto>. Only the bodies used here are declared,
+//! prost skips the others.
+
+use super::types::DepthVersion;
+use crate::l2_book::types::parse_scaled;
+use crate::l2_book::{Order, Price, PriceSize, Sequence, Size};
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PushDataV3ApiWrapper {
+    #[prost(string, tag = "1")]
+    pub channel: String,
+
+    #[prost(string, optional, tag = "3")]
+    pub symbol: Option<String>,
+
+    #[prost(string, optional, tag = "4")]
+    pub symbol_id: Option<String>,
+
+    #[prost(int64, optional, tag = "5")]
+    pub create_time: Option<i64>,
+
+    #[prost(int64, optional, tag = "6")]
+    pub send_time: Option<i64>,
+
+    #[prost(oneof = "Body", tags = "313")]
+    pub body: Option<Body>,
+}
+
+#[derive(Clone, PartialEq, prost::Oneof)]
+pub enum Body {
+    #[prost(message, tag = "313")]
+    PublicAggreDepths(PublicAggreDepthsV3Api),
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PublicAggreDepthsV3Api {
+    #[prost(message, repeated, tag = "1")]
+    pub asks: Vec<PublicAggreDepthV3ApiItem>,
+
+    #[prost(message, repeated, tag = "2")]
+    pub bids: Vec<PublicAggreDepthV3ApiItem>,
+
+    #[prost(string, tag = "3")]
+    pub event_type: String,
+
+    #[prost(string, tag = "4")]
+    pub from_version: String,
+
+    #[prost(string, tag = "5")]
+    pub to_version: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PublicAggreDepthV3ApiItem {
+    #[prost(string, tag = "1")]
+    pub price: String,
+
+    #[prost(string, tag = "2")]
+    pub quantity: String,
+}
+
+impl PublicAggreDepthV3ApiItem {
+    fn price_size(&self) -> Option<PriceSize> {
+        Some(PriceSize(Price(parse_scaled(&self.price)?), Size(parse_scaled(&self.quantity)?)))
+    }
+}
+
+/// Decodes a binary frame, control messages such as subscription acks are sent as JSON text instead
+pub fn decode(frame: &[u8]) -> Result<PushDataV3ApiWrapper, prost::DecodeError> {
+    prost::Message::decode(frame)
+}
+
+impl PushDataV3ApiWrapper {
+    /// Aggregated depth update, `None` for other bodies or malformed numbers
+    pub fn into_order(self) -> Option<Order<DepthVersion>> {
+        let Some(Body::PublicAggreDepths(depth)) = self.body else {
+            return None;
+        };
+
+        let levels = |items: &[PublicAggreDepthV3ApiItem]| items.iter().map(|i| i.price_size()).collect::<Option<Vec<_>>>();
+
+        let version = DepthVersion {
+            from_version: depth.from_version.parse().ok()?,
+            to_version: depth.to_version.parse().ok()?,
+        };
+
+        Some(Order {
+            id: Sequence(version.to_version),
+            bids: levels(&depth.bids)?,
+            asks: levels(&depth.asks)?,
+            is_snapshot: false,
+            ts_ms: self.send_time.or(self.create_time).unwrap_or_default() as u64,
+            o: version,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Body, PublicAggreDepthV3ApiItem, PublicAggreDepthsV3Api, PushDataV3ApiWrapper, decode};
+    use crate::l2_book::{Price, PriceSize, Size};
+    use prost::Message;
+
+    #[test]
+    fn decode_aggre_depth() {
+        let msg = PushDataV3ApiWrapper {
+            channel: "spot@public.aggre.depth.v3.api.pb@100ms@BTCUSDT".into(),
+            symbol: Some("BTCUSDT".into()),
+            symbol_id: None,
+            create_time: None,
+            send_time: Some(1736411507002),
+            body: Some(Body::PublicAggreDepths(PublicAggreDepthsV3Api {
+                asks: vec![],
+                bids: vec![PublicAggreDepthV3ApiItem {
+                    price: "92877.58".into(),
+                    quantity: "0.00000000".into(),
+                }],
+                event_type: "spot@public.aggre.depth.v3.api.pb@100ms".into(),
+                from_version: "10589632359".into(),
+                to_version: "10589632360".into(),
+            })),
+        };
+
+        let order = decode(&msg.encode_to_vec()).unwrap().into_order().unwrap();
+        assert_eq!(order.id.val(), 10589632360);
+        assert_eq!(order.o.from_version, 10589632359);
+        assert_eq!(order.ts_ms, 1736411507002);
+        assert_eq!(order.bids[0], PriceSize(Price::from(92877.58), Size(0)));
+    }
+}
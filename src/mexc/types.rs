@@ -0,0 +1,55 @@
+use crate::l2_book;
+use serde::{Deserialize, Serialize};
+
+/// Range of book versions covered by a depth update
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DepthVersion {
+    pub from_version: u64,
+    pub to_version: u64,
+}
+
+/// `GET /api/v3/depth`
+#[derive(Deserialize, Debug)]
+pub struct DepthSnapshot {
+    #[serde(rename = "lastUpdateId")]
+    pub last_update_id: u64,
+
+    pub bids: Vec<l2_book::PriceSize>,
+
+    pub asks: Vec<l2_book::PriceSize>,
+
+    pub timestamp: Option<u64>,
+}
+
+impl From<DepthSnapshot> for l2_book::Order<DepthVersion> {
+    fn from(val: DepthSnapshot) -> Self {
+        l2_book::Order {
+            id: l2_book::Sequence(val.last_update_id),
+            bids: val.bids,
+            asks: val.asks,
+            is_snapshot: true,
+            ts_ms: val.timestamp.unwrap_or_default(),
+            o: DepthVersion {
+                from_version: val.last_update_id,
+                to_version: val.last_update_id,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DepthSnapshot;
+    use crate::l2_book::{Order, Price, PriceSize, Size};
+
+    #[test]
+    fn deserialize_snapshot() {
+        let d = r#"{"lastUpdateId":10589632358,"bids":[["92877.58","0.00345"]],"asks":[["92877.59","1.2"]],"timestamp":1736411507002}"#;
+        let msg: DepthSnapshot = serde_json::from_str(d).unwrap();
+
+        let order: Order<_> = msg.into();
+        assert!(order.is_snapshot);
+        assert_eq!(order.o.to_version, 10589632358);
+        assert_eq!(order.asks[0], PriceSize(Price::from(92877.59), Size::from(1.2)));
+    }
+}
@@ -0,0 +1,23 @@
+pub const WS_URL: &str = "wss://ws.okx.com:8443/ws/v5/public";
+
+/// `books` channel, see [`Venue`](crate::venue::Venue)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Okx;
+
+/// Subscribe request for the `books` channel of each instrument, e.g. `BTC-USDT`
+pub fn subscribe(inst_ids: &[&str]) -> String {
+    request("subscribe", inst_ids)
+}
+
+pub fn unsubscribe(inst_ids: &[&str]) -> String {
+    request("unsubscribe", inst_ids)
+}
+
+fn request(op: &str, inst_ids: &[&str]) -> String {
+    let args: Vec<_> = inst_ids
+        .iter()
+        .map(|id| serde_json::json!({ "channel": "books", "instId": id }))
+        .collect();
+
+    serde_json::json!({ "op": op, "args": args }).to_string()
+}
@@ -0,0 +1,194 @@
+use super::api::{Okx, WS_URL, subscribe, unsubscribe};
+use super::decode::BooksDecoder;
+use super::types::BookSeq;
+use crate::l2_book::tokio::{Book as AsyncBook, NoSnapshotFetcher};
+use crate::l2_book::{BookSequencer, Levels, Order, Price, Sequence};
+use crate::venue::Venue;
+use crate::ws::Message;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Number of levels per side covered by the checksum
+const CHECKSUM_DEPTH: usize = 25;
+
+/// Each message chains on the previous `seqId` with `prevSeqId`, ids are not contiguous
+#[derive(Default)]
+pub struct OkxBookSequencer {
+    strings: LevelStrings,
+}
+
+impl BookSequencer<BookSeq> for OkxBookSequencer {
+    fn is_first_event(&self, cur_seq: Sequence, update: &Order<BookSeq>) -> bool {
+        update.o.prev_seq_id == cur_seq.val() as i64
+    }
+
+    fn is_stale(&self, cur_seq: Sequence, update: &Order<BookSeq>) -> bool {
+        update.o.prev_seq_id > cur_seq.val() as i64
+    }
+
+    fn is_next(&self, cur_seq: Sequence, update: &Order<BookSeq>) -> bool {
+        update.o.prev_seq_id == cur_seq.val() as i64
+    }
+
+    fn on_apply(&mut self, update: &Order<BookSeq>) {
+        self.strings.apply(update);
+    }
+
+    fn is_valid(&self, book: &Levels<'_>, update: &Order<BookSeq>) -> bool {
+        checksum(book, &self.strings) == update.o.checksum
+    }
+}
+
+/// `price:size` of every level as last sent, by price
+#[derive(Debug, Default)]
+pub struct LevelStrings {
+    bids: BTreeMap<Price, String>,
+    asks: BTreeMap<Price, String>,
+}
+
+impl LevelStrings {
+    pub fn apply(&mut self, order: &Order<BookSeq>) {
+        if order.is_snapshot {
+            self.bids.clear();
+            self.asks.clear();
+        }
+
+        for (side, levels) in [(&mut self.bids, &order.o.levels.bids), (&mut self.asks, &order.o.levels.asks)] {
+            for level in levels {
+                match level.size.0 {
+                    0 => side.remove(&level.price),
+                    _ => side.insert(level.price, format!("{}:{}", level.price_str, level.size_str)),
+                };
+            }
+        }
+    }
+}
+
+/// Signed CRC32 of `bid:size:ask:size:...` interleaved over the top 25 levels, written
+/// as sent. Levels missing from `strings` are written without trailing zeros.
+pub fn checksum(book: &Levels<'_>, strings: &LevelStrings) -> i32 {
+    let mut bids = book.bids().take(CHECKSUM_DEPTH);
+    let mut asks = book.asks().take(CHECKSUM_DEPTH);
+    let mut hasher = crc32fast::Hasher::new();
+    let mut fallback = String::new();
+    let mut first = true;
+
+    loop {
+        let (bid, ask) = (bids.next(), asks.next());
+        if bid.is_none() && ask.is_none() {
+            break;
+        }
+
+        for (side, level) in [(&strings.bids, bid), (&strings.asks, ask)] {
+            let Some((p, s)) = level else { continue };
+
+            let field = match side.get(&p) {
+                Some(field) => field.as_str(),
+                None => {
+                    fallback.clear();
+                    let _ = write!(fallback, "{}:{}", p, s);
+                    fallback.as_str()
+                }
+            };
+
+            if !first {
+                hasher.update(b":");
+            }
+            hasher.update(field.as_bytes());
+            first = false;
+        }
+    }
+
+    hasher.finalize() as i32
+}
+
+impl Venue for Okx {
+    type Seq = BookSeq;
+    type Sequencer = OkxBookSequencer;
+    type Fetcher = NoSnapshotFetcher;
+    type Decoder = BooksDecoder;
+
+    fn stream_url(&self, _inst_id: &str) -> String {
+        WS_URL.to_string()
+    }
+
+    fn subscribe(&self, inst_id: &str) -> Vec<Message> {
+        vec![subscribe(&[inst_id]).into()]
+    }
+
+    fn unsubscribe(&self, inst_id: &str) -> Vec<Message> {
+        vec![unsubscribe(&[inst_id]).into()]
+    }
+
+    fn decoder(&self) -> Self::Decoder {
+        BooksDecoder
+    }
+
+    fn sequencer(&self) -> Self::Sequencer {
+        OkxBookSequencer::default()
+    }
+
+    fn fetcher(&self) -> Option<Self::Fetcher> {
+        None
+    }
+}
+
+pub struct Book;
+
+impl Book {
+    /// Streamed book, resubscribe to the instrument on [`snapshot_requested`](crate::l2_book::tokio::BookWriter::snapshot_requested)
+    pub fn new_okx(inst_id: impl Into<String>, depth: usize, interval: Duration) -> AsyncBook<BookSeq> {
+        AsyncBook::new_streamed(inst_id.into(), OkxBookSequencer::default(), depth, interval)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::OkxBookSequencer;
+    use crate::l2_book::{BookAction, BookFsm, Order, Price, PriceSize, Sequence, Size};
+    use crate::okx::types::{BookSeq, BooksMessage};
+
+    fn order(prev_seq_id: i64, seq_id: i64, checksum: i32, bids: &[(f64, f64)], asks: &[(f64, f64)]) -> Order<BookSeq> {
+        let levels = |l: &[(f64, f64)]| l.iter().map(|&(p, s)| PriceSize(Price::from(p), Size::from(s))).collect();
+
+        Order {
+            id: Sequence(seq_id as u64),
+            bids: levels(bids),
+            asks: levels(asks),
+            is_snapshot: prev_seq_id == -1,
+            ts_ms: 0,
+            o: BookSeq {
+                seq_id,
+                prev_seq_id,
+                checksum,
+                levels: Default::default(),
+            },
+        }
+    }
+
+    #[test]
+    fn checksum_mismatch_resyncs() {
+        let mut fsm = BookFsm::new(OkxBookSequencer::default());
+
+        // "3366.1:7:3366.8:9:3366:6:3368:8"
+        let snapshot = order(-1, 10, -1881014294, &[(3366.1, 7.0), (3366.0, 6.0)], &[(3366.8, 9.0), (3368.0, 8.0)]);
+        assert_eq!(BookAction::Ok, fsm.update(snapshot));
+
+        // Removing a level without updating the checksum
+        let update = order(10, 15, -1881014294, &[(3366.0, 0.0)], &[]);
+        assert_eq!(BookAction::RetrieveSnapshot, fsm.update(update));
+    }
+
+    #[test]
+    fn checksum_keeps_trailing_zeros() {
+        let mut fsm = BookFsm::new(OkxBookSequencer::default());
+
+        // "3366.10:7.0:3366.80:9"
+        let d = r#"{"arg":{"channel":"books","instId":"ETH-USDT"},"action":"snapshot","data":[{"asks":[["3366.80","9","0","1"]],"bids":[["3366.10","7.0","0","1"]],"ts":"1597026383085","checksum":803302687,"prevSeqId":-1,"seqId":10}]}"#;
+        let msg: BooksMessage = serde_json::from_str(d).unwrap();
+        let snapshot = Vec::<Order<BookSeq>>::from(msg).remove(0);
+
+        assert_eq!(BookAction::Ok, fsm.update(snapshot));
+    }
+}
@@ -0,0 +1,30 @@
+use super::types::{BookSeq, BooksMessage};
+use crate::feed::{Control, Decoder, Event};
+use crate::l2_book::Order;
+use serde::Deserialize;
+use serde::de::IgnoredAny;
+
+/// `books` channel messages, a message can carry several orders
+#[derive(Debug, Default)]
+pub struct BooksDecoder;
+
+/// Subscription acks and errors are sent as `event` messages
+#[derive(Deserialize)]
+struct EventField {
+    event: Option<IgnoredAny>,
+}
+
+impl Decoder for BooksDecoder {
+    type Seq = BookSeq;
+
+    fn decode(&mut self, frame: &mut [u8], events: &mut Vec<Event<BookSeq>>) {
+        match serde_json::from_slice::<EventField>(frame) {
+            Ok(EventField { event: Some(_) }) => events.push(Event::Control(Control::Message(frame.to_vec()))),
+            Ok(_) => match serde_json::from_slice::<BooksMessage>(frame) {
+                Ok(msg) => events.extend(Vec::<Order<BookSeq>>::from(msg).into_iter().map(Event::Book)),
+                Err(e) => events.push(Event::decode_error(e)),
+            },
+            Err(e) => events.push(Event::decode_error(e)),
+        }
+    }
+}
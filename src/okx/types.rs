@@ -0,0 +1,143 @@
+use crate::l2_book;
+use crate::l2_book::types::{parse_scaled, u64_from_str};
+use serde::de::{self, IgnoredAny};
+use serde::{Deserialize, Deserializer, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Snapshot,
+    Update,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BooksMessage<'a> {
+    #[serde(borrow)]
+    pub arg: Arg<'a>,
+
+    pub action: Action,
+
+    pub data: Vec<BookData>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Arg<'a> {
+    #[serde(borrow)]
+    pub channel: std::borrow::Cow<'a, str>,
+
+    #[serde(rename = "instId")]
+    #[serde(borrow)]
+    pub inst_id: std::borrow::Cow<'a, str>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BookData {
+    pub asks: Vec<Level>,
+
+    pub bids: Vec<Level>,
+
+    #[serde(with = "u64_from_str")]
+    pub ts: u64,
+
+    #[serde(flatten)]
+    pub seq: BookSeq,
+}
+
+/// `[price, size, deprecated, order count]`, the strings are kept as sent since the
+/// checksum is computed over them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Level {
+    pub price: l2_book::Price,
+    pub size: l2_book::Size,
+    pub price_str: String,
+    pub size_str: String,
+}
+
+impl<'de> Deserialize<'de> for Level {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (price_str, size_str, _, _) = <(String, String, IgnoredAny, IgnoredAny)>::deserialize(deserializer)?;
+        let parse = |s: &str| parse_scaled(s).ok_or_else(|| de::Error::custom(format!("invalid decimal: {}", s)));
+
+        Ok(Level {
+            price: l2_book::Price(parse(&price_str)?),
+            size: l2_book::Size(parse(&size_str)?),
+            price_str,
+            size_str,
+        })
+    }
+}
+
+impl From<&Level> for l2_book::PriceSize {
+    fn from(val: &Level) -> Self {
+        l2_book::PriceSize(val.price, val.size)
+    }
+}
+
+/// Levels of one message as sent, see [`Level`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WireLevels {
+    pub bids: Vec<Level>,
+    pub asks: Vec<Level>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BookSeq {
+    #[serde(rename = "seqId")]
+    pub seq_id: i64,
+
+    /// `-1` on snapshots
+    #[serde(rename = "prevSeqId")]
+    pub prev_seq_id: i64,
+
+    pub checksum: i32,
+
+    #[serde(skip)]
+    pub levels: WireLevels,
+}
+
+impl From<BooksMessage<'_>> for Vec<l2_book::Order<BookSeq>> {
+    fn from(val: BooksMessage<'_>) -> Self {
+        let is_snapshot = val.action == Action::Snapshot;
+
+        val.data
+            .into_iter()
+            .map(|data| l2_book::Order {
+                id: l2_book::Sequence(data.seq.seq_id as u64),
+                bids: data.bids.iter().map(Into::into).collect(),
+                asks: data.asks.iter().map(Into::into).collect(),
+                is_snapshot,
+                ts_ms: data.ts,
+                o: BookSeq {
+                    levels: WireLevels {
+                        bids: data.bids,
+                        asks: data.asks,
+                    },
+                    ..data.seq
+                },
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Action, BooksMessage};
+    use crate::l2_book::{Order, Price, PriceSize, Size};
+
+    #[test]
+    fn deserialize_books() {
+        let d = r#"{"arg":{"channel":"books","instId":"BTC-USDT"},"action":"snapshot","data":[{"asks":[["8476.98","415","0","13"],["8477","7","0","2"]],"bids":[["8476","256","0","12"]],"ts":"1597026383085","checksum":-855196043,"prevSeqId":-1,"seqId":123456}]}"#;
+        let msg: BooksMessage = serde_json::from_str(d).unwrap();
+
+        assert_eq!(msg.action, Action::Snapshot);
+        assert_eq!(msg.arg.inst_id, "BTC-USDT");
+        assert_eq!(msg.data[0].ts, 1597026383085);
+        assert_eq!(msg.data[0].seq.prev_seq_id, -1);
+        assert_eq!(msg.data[0].seq.checksum, -855196043);
+
+        let orders: Vec<Order<_>> = msg.into();
+        assert!(orders[0].is_snapshot);
+        assert_eq!(orders[0].id.val(), 123456);
+        assert_eq!(orders[0].asks[1], PriceSize(Price::from(8477.0), Size::from(7.0)));
+    }
+}
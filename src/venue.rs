@@ -0,0 +1,107 @@
+use crate::feed::{Decoder, Event, spawn_feed};
+use crate::l2_book::BookSequencer;
+use crate::l2_book::tokio::{Book as AsyncBook, SnapshotFetcher};
+use crate::ws::{self, Backoff, ConnectOptions, Message, WsError};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Everything needed to keep a book of one venue: where to connect, what to send,
+/// how to decode frames and how to sequence and recover them.
+pub trait Venue: Send + 'static {
+    /// Sequencing data carried by the venue's orders
+    type Seq: Send + 'static;
+    type Sequencer: BookSequencer<Self::Seq> + Send + 'static;
+    type Fetcher: SnapshotFetcher<Self::Seq> + Send + 'static;
+    type Decoder: Decoder<Seq = Self::Seq>;
+
+    fn stream_url(&self, symbol: &str) -> String;
+
+    /// Messages sent once connected, and again when a streamed book needs a new snapshot
+    fn subscribe(&self, symbol: &str) -> Vec<Message>;
+
+    /// Messages sent before resubscribing
+    fn unsubscribe(&self, _symbol: &str) -> Vec<Message> {
+        vec![]
+    }
+
+    fn decoder(&self) -> Self::Decoder;
+
+    fn sequencer(&self) -> Self::Sequencer;
+
+    /// `None` for venues sending their snapshots on the stream
+    fn fetcher(&self) -> Option<Self::Fetcher>;
+}
+
+pub struct RunOptions<V> {
+    pub venue: V,
+    pub depth: usize,
+    pub interval: Duration,
+    /// Timeouts, TLS and reconnects of the stream, it reconnects with the default [`Backoff`]
+    /// unless changed. Subscriptions are set from the venue.
+    pub connect: ConnectOptions,
+}
+
+impl<V> RunOptions<V> {
+    pub fn new(venue: V) -> Self {
+        Self {
+            venue,
+            depth: 1000,
+            interval: Duration::ZERO,
+            connect: ConnectOptions::new().reconnect(Backoff::default()),
+        }
+    }
+
+    pub fn depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn connect(mut self, connect: ConnectOptions) -> Self {
+        self.connect = connect;
+        self
+    }
+
+    /// Delays between reconnection attempts once the stream drops
+    pub fn backoff(mut self, backoff: Backoff) -> Self {
+        self.connect.reconnect = Some(backoff);
+        self
+    }
+}
+
+impl<V: Default> Default for RunOptions<V> {
+    fn default() -> Self {
+        Self::new(V::default())
+    }
+}
+
+/// Connects to the venue, subscribes and feeds decoded orders into a new book.
+/// Events other than book updates are returned along with the book, see [`spawn_feed`].
+///
+/// A dropped or stale connection is reopened and resubscribed as set in [`RunOptions::connect`],
+/// the book resyncs from a new snapshot.
+pub async fn run_book<V: Venue>(symbol: impl Into<String>, opts: RunOptions<V>) -> Result<(AsyncBook<V::Seq>, mpsc::Receiver<Event<V::Seq>>), WsError> {
+    let symbol = symbol.into();
+    let RunOptions {
+        venue,
+        depth,
+        interval,
+        connect,
+    } = opts;
+
+    let ws = ws::connect_with(&venue.stream_url(&symbol), connect.subscriptions(venue.subscribe(&symbol))).await?;
+
+    let book = match venue.fetcher() {
+        Some(fetcher) => AsyncBook::new(symbol.clone(), venue.sequencer(), fetcher, depth, interval),
+        None => AsyncBook::new_streamed(symbol.clone(), venue.sequencer(), depth, interval),
+    };
+
+    let resubscribe = venue.unsubscribe(&symbol).into_iter().chain(venue.subscribe(&symbol)).collect();
+    let events = spawn_feed(ws, venue.decoder(), book.writer(), resubscribe);
+
+    Ok((book, events))
+}
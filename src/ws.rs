@@ -2,31 +2,335 @@ use fastwebsockets::{Frame, OpCode, Payload};
 use http_body_util::Empty;
 use hyper::body::Bytes;
 use hyper_util::rt::TokioExecutor;
+use std::collections::hash_map::RandomState;
 use std::fmt;
+use std::hash::{BuildHasher, Hasher};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::net::TcpStream;
 use tokio::sync::mpsc::{Receiver, Sender, channel};
+use tokio::time::{Instant, Interval, interval_at, sleep_until};
 use tokio_rustls::client::{TlsConnector, TlsStream};
-use tokio_rustls::rustls::ClientConfig;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName};
+use tokio_rustls::rustls::{ClientConfig, RootCertStore};
 use url::Url;
 
+/// Dropping `rx` closes the connection with a close handshake
 pub struct WsHandle {
     pub rx: Receiver<Result<Vec<u8>, WsError>>,
-    pub tx: Sender<Vec<u8>>,
+    pub tx: Sender<Message>,
 }
 
+/// Frame to send on a connection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    Text(String),
+    Binary(Vec<u8>),
+    Ping(Vec<u8>),
+    /// Closes the connection with a close handshake, it isn't reopened afterwards
+    Close(u16, String),
+}
+
+impl From<String> for Message {
+    fn from(val: String) -> Self {
+        Message::Text(val)
+    }
+}
+
+impl From<&str> for Message {
+    fn from(val: &str) -> Self {
+        Message::Text(val.to_string())
+    }
+}
+
+impl From<Vec<u8>> for Message {
+    fn from(val: Vec<u8>) -> Self {
+        Message::Binary(val)
+    }
+}
+
+impl Message {
+    fn into_frame(self) -> Frame<'static> {
+        match self {
+            Message::Text(text) => Frame::text(Payload::Owned(text.into_bytes())),
+            Message::Binary(data) => Frame::binary(Payload::Owned(data)),
+            Message::Ping(data) => Frame::new(true, OpCode::Ping, None, Payload::Owned(data)),
+            Message::Close(code, reason) => Frame::close(code, reason.as_bytes()),
+        }
+    }
+}
+
+/// Normal closure code
+pub const CLOSE_NORMAL: u16 = 1000;
+
+/// Code reported when the server's close frame carries none
+pub const CLOSE_NO_STATUS: u16 = 1005;
+
+/// Longest wait for the server's reply to a close frame
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+type Stream = fastwebsockets::FragmentCollector<hyper_util::rt::TokioIo<hyper::upgrade::Upgraded>>;
+
 pub async fn connect(url: &str) -> Result<WsHandle, WsError> {
-    let _ = tokio_rustls::rustls::crypto::ring::default_provider().install_default();
+    connect_with(url, ConnectOptions::default()).await
+}
+
+/// Settings of [`connect_with`], nothing is timed out and nothing reconnects by default
+#[derive(Debug, Clone, Default)]
+pub struct ConnectOptions {
+    /// Interval of client pings, keeps idle connections open and lets `read_timeout` catch dead ones
+    pub ping_interval: Option<Duration>,
+    /// Longest time without any frame, pongs included
+    pub read_timeout: Option<Duration>,
+    /// Longest time without a text or binary frame
+    pub data_timeout: Option<Duration>,
+    /// Reopens the connection after any error when set
+    pub reconnect: Option<Backoff>,
+    /// Sent on every new connection before anything queued on `tx`
+    pub subscriptions: Vec<Message>,
+    pub tls: TlsOptions,
+}
 
+/// TLS settings of `wss://` connections, only the webpki roots are trusted by default
+#[derive(Debug, Clone)]
+pub struct TlsOptions {
+    pub webpki_roots: bool,
+    /// Trusts the platform's certificate store as well
+    #[cfg(feature = "native-roots")]
+    pub native_roots: bool,
+    /// Extra trusted roots, e.g. of a self-signed gateway
+    pub roots: Vec<CertificateDer<'static>>,
+    /// Certificate chain and key presented to servers requiring mutual TLS
+    pub client_auth: Option<(Vec<CertificateDer<'static>>, Arc<PrivateKeyDer<'static>>)>,
+    /// Name sent as SNI and verified against the server certificate instead of the URL's host
+    pub server_name: Option<String>,
+}
+
+impl Default for TlsOptions {
+    fn default() -> Self {
+        Self {
+            webpki_roots: true,
+            #[cfg(feature = "native-roots")]
+            native_roots: false,
+            roots: Vec::new(),
+            client_auth: None,
+            server_name: None,
+        }
+    }
+}
+
+impl ConnectOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ping_interval(mut self, interval: Duration) -> Self {
+        self.ping_interval = Some(interval);
+        self
+    }
+
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    pub fn data_timeout(mut self, timeout: Duration) -> Self {
+        self.data_timeout = Some(timeout);
+        self
+    }
+
+    pub fn reconnect(mut self, backoff: Backoff) -> Self {
+        self.reconnect = Some(backoff);
+        self
+    }
+
+    pub fn subscriptions(mut self, subscriptions: Vec<Message>) -> Self {
+        self.subscriptions = subscriptions;
+        self
+    }
+
+    /// Trusts `cert` on top of the other roots, PEM files can be read with
+    /// [`PemObject`](tokio_rustls::rustls::pki_types::pem::PemObject)
+    pub fn root_certificate(mut self, cert: CertificateDer<'static>) -> Self {
+        self.tls.roots.push(cert);
+        self
+    }
+
+    pub fn webpki_roots(mut self, enabled: bool) -> Self {
+        self.tls.webpki_roots = enabled;
+        self
+    }
+
+    #[cfg(feature = "native-roots")]
+    pub fn native_roots(mut self, enabled: bool) -> Self {
+        self.tls.native_roots = enabled;
+        self
+    }
+
+    pub fn client_auth(mut self, certs: Vec<CertificateDer<'static>>, key: PrivateKeyDer<'static>) -> Self {
+        self.tls.client_auth = Some((certs, Arc::new(key)));
+        self
+    }
+
+    pub fn server_name(mut self, name: impl Into<String>) -> Self {
+        self.tls.server_name = Some(name.into());
+        self
+    }
+}
+
+/// Opens the connection and spawns the task pumping its frames. A timed out connection
+/// ends with [`WsError::Stale`], as any other error it is reopened if `reconnect` is set.
+///
+/// Each lost connection is reported in order on `rx` as an `Err`, frames of the next
+/// connection follow it. `rx` closes once the connection is gone for good.
+pub async fn connect_with(url: &str, opts: ConnectOptions) -> Result<WsHandle, WsError> {
+    // Roots are loaded once, reconnections reuse the config
+    let tls = tls_config(&opts.tls)?;
+    let server_name = opts.tls.server_name.clone();
+    let mut ws = open(url, server_name.as_deref(), &tls).await?;
+
+    let (read_tx, read_rx) = channel(100);
+    let (write_tx, mut write_rx) = channel(100);
+    let url = url.to_string();
+
+    tokio::spawn(async move {
+        let mut attempt = 0;
+
+        loop {
+            let opened_at = Instant::now();
+            let mut received = false;
+
+            let exit = match subscribe(&mut ws, &opts.subscriptions).await {
+                Ok(_) => run(&mut ws, &mut write_rx, &read_tx, &opts, &mut received).await,
+                Err(e) => Exit::Error(e),
+            };
+
+            match exit {
+                Exit::Error(e) => {
+                    if read_tx.send(Err(e)).await.is_err() {
+                        return;
+                    }
+                }
+                Exit::Done => return,
+            }
+
+            let Some(backoff) = opts.reconnect else { return };
+
+            // A server accepting and dropping the connection right away counts as a failed attempt,
+            // otherwise it would be reconnected at the initial delay forever
+            if received || opened_at.elapsed() >= backoff.max {
+                attempt = 0;
+            }
+
+            ws = loop {
+                if backoff.max_attempts.is_some_and(|max| attempt >= max) {
+                    return;
+                }
+
+                tokio::time::sleep(backoff.delay(attempt)).await;
+                attempt += 1;
+
+                match open(&url, server_name.as_deref(), &tls).await {
+                    Ok(ws) => break ws,
+                    Err(e) => {
+                        if read_tx.send(Err(e)).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+            };
+        }
+    });
+
+    Ok(WsHandle {
+        rx: read_rx,
+        tx: write_tx,
+    })
+}
+
+/// Delay before each reconnection attempt, growing from `initial` by `factor` up to `max`.
+/// Each delay is shortened by a random share of up to `jitter`, between 0 and 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backoff {
+    pub initial: Duration,
+    pub max: Duration,
+    pub factor: f64,
+    pub jitter: f64,
+    /// Attempts in a row before giving up, `None` retries forever. Connections closed before
+    /// any data arrived or `max` elapsed don't end the row.
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_millis(100),
+            max: Duration::from_secs(30),
+            factor: 2.0,
+            jitter: 0.5,
+            max_attempts: None,
+        }
+    }
+}
+
+impl Backoff {
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let delay = self.initial.as_secs_f64() * self.factor.powi(attempt as i32);
+        let delay = delay.min(self.max.as_secs_f64());
+        Duration::from_secs_f64(delay * (1.0 - self.jitter.clamp(0.0, 1.0) * random_unit()))
+    }
+}
+
+/// Uniform value in `[0, 1)`, good enough to spread reconnects apart
+fn random_unit() -> f64 {
+    let hash = RandomState::new().build_hasher().finish();
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Connection that reopens itself after any error, with `backoff` between attempts.
+/// `subscriptions` are sent on every new connection before anything queued on `tx`.
+pub async fn connect_reconnecting(url: &str, subscriptions: Vec<Message>, backoff: Backoff) -> Result<WsHandle, WsError> {
+    connect_with(url, ConnectOptions::new().reconnect(backoff).subscriptions(subscriptions)).await
+}
+
+async fn subscribe(ws: &mut Stream, subscriptions: &[Message]) -> Result<(), WsError> {
+    for msg in subscriptions {
+        ws.write_frame(msg.clone().into_frame()).await?;
+    }
+
+    Ok(())
+}
+
+/// `server_name` overrides the URL's host for SNI and certificate verification
+async fn open(url: &str, server_name: Option<&str>, tls: &Arc<ClientConfig>) -> Result<Stream, WsError> {
     let url_parsed = Url::parse(url)?;
     let host = url_parsed.host_str().ok_or(WsError::MissingHost)?;
 
     let port = url_parsed.port_or_known_default().unwrap_or(443);
     let addr = format!("{}:{}", host, port);
 
+    let secure = match url_parsed.scheme() {
+        "wss" => true,
+        "ws" => false,
+        scheme => return Err(WsError::UnsupportedScheme(scheme.to_string())),
+    };
+
     let tcp_stream = TcpStream::connect(&addr).await?;
-    let tls_stream = tls_connect(host, tcp_stream).await?;
 
+    match secure {
+        true => {
+            let tls_stream = tls_connect(server_name.unwrap_or(host), tcp_stream, tls).await?;
+            handshake(url, host, tls_stream).await
+        }
+        false => handshake(url, host, tcp_stream).await,
+    }
+}
+
+async fn handshake<S>(url: &str, host: &str, stream: S) -> Result<Stream, WsError>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
     let req = hyper::Request::builder()
         .uri(url)
         .header("Host", host)
@@ -40,54 +344,139 @@ pub async fn connect(url: &str) -> Result<WsHandle, WsError> {
         .body(Empty::<Bytes>::new())?;
 
     let executor = TokioExecutor::new();
-    let (ws, _) = fastwebsockets::handshake::client(&executor, req, tls_stream)
+    let (ws, _) = fastwebsockets::handshake::client(&executor, req, stream)
         .await
         .map_err(|e| WsError::Handshake(format!("{:?}", e)))?;
 
-    let mut ws = fastwebsockets::FragmentCollector::new(ws);
+    Ok(fastwebsockets::FragmentCollector::new(ws))
+}
 
-    let (read_tx, read_rx) = channel(100);
-    let (write_tx, mut write_rx) = channel(100);
+enum Exit {
+    Error(WsError),
+    /// Closed on purpose, by a close message or by dropping the reader
+    Done,
+}
 
-    tokio::spawn(async move {
-        loop {
-            tokio::select! {
-                // Write ws
-                m = write_rx.recv() => {
-                    if let Some(msg) = m {
-                        // FIXME: handle tx error if needed
-                        let _ = ws.write_frame(Frame::binary(Payload::Owned(msg))).await;
+/// Pumps frames both ways until the connection fails, times out or is closed
+/// `received` is set once a data frame was read.
+async fn run(
+    ws: &mut Stream,
+    write_rx: &mut Receiver<Message>,
+    read_tx: &Sender<Result<Vec<u8>, WsError>>,
+    opts: &ConnectOptions,
+    received: &mut bool,
+) -> Exit {
+    let mut write_closed = false;
+
+    let mut ping = opts.ping_interval.map(|period| interval_at(Instant::now() + period, period));
+    let mut last_read = Instant::now();
+    let mut last_data = last_read;
+
+    loop {
+        let read_deadline = opts.read_timeout.map(|timeout| last_read + timeout);
+        let data_deadline = opts.data_timeout.map(|timeout| last_data + timeout);
+
+        tokio::select! {
+            // Write ws
+            m = write_rx.recv(), if !write_closed => {
+                match m {
+                    Some(Message::Close(code, reason)) => {
+                        close(ws, code, &reason).await;
+                        return Exit::Done;
                     }
+                    Some(msg) => {
+                        if let Err(e) = ws.write_frame(msg.into_frame()).await {
+                            return Exit::Error(e.into());
+                        }
+                    }
+                    None => write_closed = true,
                 }
+            }
 
-                // Read ws
-                res = ws.read_frame() => {
-                    match process_frame(res) {
-                        FrameResult::Msg(val) => {
-                            if read_tx.send(Ok(val)).await.is_err() {
-                                 break;
-                            }
-                        }
-                        FrameResult::Ping(val) => {
-                            if ws.write_frame(fastwebsockets::Frame::new(true, OpCode::Pong, None, fastwebsockets::Payload::Owned(val))).await.is_err() {
-                                break;
-                            }
+            _ = read_tx.closed() => {
+                close(ws, CLOSE_NORMAL, "").await;
+                return Exit::Done;
+            }
+
+            // Heartbeat
+            _ = tick(&mut ping) => {
+                if let Err(e) = ws.write_frame(Frame::new(true, OpCode::Ping, None, Payload::Owned(vec![]))).await {
+                    return Exit::Error(e.into());
+                }
+            }
+
+            _ = sleep_until_some(read_deadline) => {
+                return Exit::Error(WsError::Stale(Stale::Read(opts.read_timeout.unwrap_or_default())));
+            }
+
+            _ = sleep_until_some(data_deadline) => {
+                return Exit::Error(WsError::Stale(Stale::Data(opts.data_timeout.unwrap_or_default())));
+            }
+
+            // Read ws
+            res = ws.read_frame() => {
+                last_read = Instant::now();
+
+                match process_frame(res) {
+                    FrameResult::Msg(val) => {
+                        *received = true;
+                        if read_tx.send(Ok(val)).await.is_err() {
+                            close(ws, CLOSE_NORMAL, "").await;
+                            return Exit::Done;
                         }
-                        FrameResult::Error(e) => {
-                            let _ = read_tx.send(Err(e)).await;
-                            break;
+
+                        // Time spent waiting on a slow reader isn't server silence
+                        last_read = Instant::now();
+                        last_data = last_read;
+                    }
+                    FrameResult::Ping(val) => {
+                        if let Err(e) = ws
+                            .write_frame(fastwebsockets::Frame::new(true, OpCode::Pong, None, fastwebsockets::Payload::Owned(val)))
+                            .await
+                        {
+                            return Exit::Error(e.into());
                         }
-                        _ => {}
                     }
+                    FrameResult::Error(e) => return Exit::Error(e),
+                    FrameResult::None => {}
                 }
             }
         }
-    });
+    }
+}
 
-    Ok(WsHandle {
-        rx: read_rx,
-        tx: write_tx,
+/// Sends a close frame and waits for the server to answer with its own
+async fn close(ws: &mut Stream, code: u16, reason: &str) {
+    if ws.write_frame(Frame::close(code, reason.as_bytes())).await.is_err() {
+        return;
+    }
+
+    let _ = tokio::time::timeout(CLOSE_TIMEOUT, async {
+        loop {
+            match ws.read_frame().await {
+                Ok(frame) if frame.opcode == OpCode::Close => return,
+                Ok(_) => continue,
+                Err(_) => return,
+            }
+        }
     })
+    .await;
+}
+
+async fn tick(interval: &mut Option<Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+async fn sleep_until_some(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
 }
 
 fn process_frame(res: Result<Frame<'_>, fastwebsockets::WebSocketError>) -> FrameResult {
@@ -115,9 +504,16 @@ fn process_frame(res: Result<Frame<'_>, fastwebsockets::WebSocketError>) -> Fram
             };
             FrameResult::Ping(payload)
         }
-        OpCode::Close => FrameResult::Error(WsError::WebSocket(
-            fastwebsockets::WebSocketError::ConnectionClosed,
-        )),
+        OpCode::Close => {
+            let (code, reason) = match frame.payload.len() {
+                0 | 1 => (CLOSE_NO_STATUS, String::new()),
+                _ => (
+                    u16::from_be_bytes([frame.payload[0], frame.payload[1]]),
+                    String::from_utf8_lossy(&frame.payload[2..]).into_owned(),
+                ),
+            };
+            FrameResult::Error(WsError::Closed { code, reason })
+        }
         _ => FrameResult::None,
     }
 }
@@ -129,18 +525,35 @@ pub enum FrameResult {
     Error(WsError),
 }
 
-async fn tls_connect(host: &str, tcp_stream: TcpStream) -> Result<TlsStream<TcpStream>, WsError> {
-    let root_store = tokio_rustls::rustls::RootCertStore {
-        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+fn tls_config(tls: &TlsOptions) -> Result<Arc<ClientConfig>, WsError> {
+    let _ = tokio_rustls::rustls::crypto::ring::default_provider().install_default();
+
+    let mut root_store = RootCertStore::empty();
+    if tls.webpki_roots {
+        root_store.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    }
+
+    #[cfg(feature = "native-roots")]
+    if tls.native_roots {
+        root_store.add_parsable_certificates(rustls_native_certs::load_native_certs().certs);
+    }
+
+    for cert in tls.roots.iter() {
+        root_store.add(cert.clone())?;
+    }
+
+    let builder = ClientConfig::builder().with_root_certificates(root_store);
+    let config = match &tls.client_auth {
+        Some((certs, key)) => builder.with_client_auth_cert(certs.clone(), key.clone_key())?,
+        None => builder.with_no_client_auth(),
     };
 
-    let config = ClientConfig::builder()
-        .with_root_certificates(root_store)
-        .with_no_client_auth();
+    Ok(Arc::new(config))
+}
 
-    let connector = TlsConnector::from(Arc::new(config));
-    let domain = tokio_rustls::rustls::pki_types::ServerName::try_from(host.to_string())
-        .map_err(|e| WsError::InvalidDns(e.to_string()))?;
+async fn tls_connect(server_name: &str, tcp_stream: TcpStream, config: &Arc<ClientConfig>) -> Result<TlsStream<TcpStream>, WsError> {
+    let connector = TlsConnector::from(config.clone());
+    let domain = ServerName::try_from(server_name.to_string()).map_err(|e| WsError::InvalidDns(e.to_string()))?;
 
     Ok(connector.connect(domain, tcp_stream).await?)
 }
@@ -154,7 +567,21 @@ pub enum WsError {
     Http(http::Error),
     WebSocket(fastwebsockets::WebSocketError),
     MissingHost,
+    /// URL scheme other than `ws` or `wss`
+    UnsupportedScheme(String),
     Handshake(String),
+    /// Connection considered dead by a timeout of [`ConnectOptions`]
+    Stale(Stale),
+    /// Server closed the connection
+    Closed { code: u16, reason: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stale {
+    /// Nothing was read for the duration, server pings and pongs included
+    Read(Duration),
+    /// No data was read for the duration
+    Data(Duration),
 }
 
 impl fmt::Display for WsError {
@@ -167,7 +594,11 @@ impl fmt::Display for WsError {
             WsError::Http(e) => write!(f, "HTTP Error: {}", e),
             WsError::WebSocket(e) => write!(f, "WebSocket Error: {}", e),
             WsError::MissingHost => write!(f, "No host in URL"),
+            WsError::UnsupportedScheme(s) => write!(f, "Unsupported URL scheme: {}", s),
             WsError::Handshake(e) => write!(f, "Handshake failed: {}", e),
+            WsError::Stale(Stale::Read(d)) => write!(f, "Connection stale: nothing read for {:?}", d),
+            WsError::Stale(Stale::Data(d)) => write!(f, "Connection stale: no data for {:?}", d),
+            WsError::Closed { code, reason } => write!(f, "Closed by server ({}): {}", code, reason),
         }
     }
 }
@@ -182,7 +613,10 @@ impl std::error::Error for WsError {
             WsError::Http(e) => Some(e),
             WsError::WebSocket(e) => Some(e),
             WsError::MissingHost => None,
+            WsError::UnsupportedScheme(_) => None,
             WsError::Handshake(_) => None,
+            WsError::Stale(_) => None,
+            WsError::Closed { .. } => None,
         }
     }
 }
@@ -216,3 +650,260 @@ impl From<fastwebsockets::WebSocketError> for WsError {
         WsError::WebSocket(e)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{Backoff, ConnectOptions, FrameResult, Stale, WsError, connect, connect_with, process_frame};
+    use fastwebsockets::Frame;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+    use tokio_rustls::TlsAcceptor;
+    use tokio_rustls::rustls::ServerConfig;
+    use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+
+    /// Answers the client's upgrade request without checking it
+    async fn upgrade<S: AsyncRead + AsyncWrite + Unpin>(socket: &mut S) {
+        let mut req = vec![];
+        while !req.ends_with(b"\r\n\r\n") {
+            req.push(socket.read_u8().await.unwrap());
+        }
+
+        let resp = "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\r\n";
+        socket.write_all(resp.as_bytes()).await.unwrap();
+    }
+
+    /// Plain `ws://` server accepting one connection and writing raw `frames` after the handshake,
+    /// bytes sent by the client come out of the receiver
+    async fn serve(frames: Vec<u8>) -> (String, mpsc::UnboundedReceiver<Vec<u8>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let (client_tx, client_rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            upgrade(&mut socket).await;
+            socket.write_all(&frames).await.unwrap();
+
+            // Keep the connection open until the client is done
+            let mut buf = [0; 1024];
+            while let Ok(n @ 1..) = socket.read(&mut buf).await {
+                let _ = client_tx.send(buf[..n].to_vec());
+            }
+        });
+
+        (url, client_rx)
+    }
+
+    #[tokio::test]
+    async fn plain_connection_closed_by_server() {
+        let mut frames = vec![0x81, 5];
+        frames.extend(b"hello");
+        frames.extend([0x88, 5, 0x0f, 0xa0]);
+        frames.extend(b"bye");
+
+        let (url, _client) = serve(frames).await;
+        let mut ws = connect(&url).await.unwrap();
+        assert_eq!(ws.rx.recv().await.unwrap().unwrap(), b"hello");
+        assert!(matches!(ws.rx.recv().await.unwrap(), Err(WsError::Closed { code: 4000, reason }) if reason == "bye"));
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_scheme() {
+        assert!(matches!(connect("http://127.0.0.1:1").await, Err(WsError::UnsupportedScheme(s)) if s == "http"));
+    }
+
+    /// Acceptor presenting a self-signed certificate for `relay.internal`
+    fn self_signed() -> (TlsAcceptor, CertificateDer<'static>) {
+        let cert = rcgen::generate_simple_self_signed(vec!["relay.internal".to_string()]).unwrap();
+        let der = CertificateDer::from(cert.cert.der().to_vec());
+        let key = PrivateKeyDer::from(PrivatePkcs8KeyDer::from(cert.key_pair.serialize_der()));
+
+        let _ = tokio_rustls::rustls::crypto::ring::default_provider().install_default();
+        let config = ServerConfig::builder()
+            .with_no_client_auth()
+            .with_single_cert(vec![der.clone()], key)
+            .unwrap();
+
+        (TlsAcceptor::from(Arc::new(config)), der)
+    }
+
+    #[tokio::test]
+    async fn self_signed_server_across_reconnects() {
+        let (acceptor, der) = self_signed();
+        // Certificate is only valid for `relay.internal`, which the client must verify instead of the IP
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("wss://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            // Sends one frame and closes, the client keeps the first socket until it reconnected
+            for payload in [b"one", b"two"] {
+                let (socket, _) = listener.accept().await.unwrap();
+                let acceptor = acceptor.clone();
+                tokio::spawn(async move {
+                    let mut socket = acceptor.accept(socket).await.unwrap();
+                    upgrade(&mut socket).await;
+                    socket.write_all(&[0x81, 3]).await.unwrap();
+                    socket.write_all(payload).await.unwrap();
+                    socket.write_all(&[0x88, 0]).await.unwrap();
+                    socket.flush().await.unwrap();
+
+                    let mut buf = [0; 1024];
+                    while let Ok(1..) = socket.read(&mut buf).await {}
+                });
+            }
+        });
+
+        let backoff = Backoff {
+            initial: Duration::from_millis(10),
+            max_attempts: Some(1),
+            ..Backoff::default()
+        };
+        let opts = ConnectOptions::new()
+            .webpki_roots(false)
+            .root_certificate(der)
+            .server_name("relay.internal")
+            .reconnect(backoff);
+        let mut ws = connect_with(&url, opts).await.unwrap();
+
+        assert_eq!(ws.rx.recv().await.unwrap().unwrap(), b"one");
+        assert!(matches!(ws.rx.recv().await.unwrap(), Err(WsError::Closed { .. })));
+        assert_eq!(ws.rx.recv().await.unwrap().unwrap(), b"two");
+    }
+
+    #[tokio::test]
+    async fn untrusted_server_is_rejected() {
+        let (acceptor, _) = self_signed();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("wss://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let _ = acceptor.accept(socket).await;
+        });
+
+        let opts = ConnectOptions::new().webpki_roots(false).server_name("relay.internal");
+        assert!(matches!(connect_with(&url, opts).await, Err(WsError::Io(_))));
+    }
+
+    #[tokio::test]
+    async fn stale_connection_times_out() {
+        // Server pings keep the connection readable, only the data timeout fires
+        let (url, _client) = serve(vec![0x89, 0]).await;
+        let opts = ConnectOptions::new()
+            .read_timeout(Duration::from_secs(5))
+            .data_timeout(Duration::from_millis(50));
+        let mut ws = connect_with(&url, opts).await.unwrap();
+        assert!(matches!(ws.rx.recv().await.unwrap(), Err(WsError::Stale(Stale::Data(_)))));
+    }
+
+    #[tokio::test]
+    async fn silent_connection_times_out() {
+        let (url, _client) = serve(vec![]).await;
+        let opts = ConnectOptions::new().read_timeout(Duration::from_millis(50));
+        let mut ws = connect_with(&url, opts).await.unwrap();
+        assert!(matches!(ws.rx.recv().await.unwrap(), Err(WsError::Stale(Stale::Read(_)))));
+    }
+
+    #[tokio::test]
+    async fn slow_reader_is_not_silence() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            upgrade(&mut socket).await;
+
+            // One more frame than the reader buffers, then a late one
+            for _ in 0..101 {
+                socket.write_all(&[0x81, 1, b'a']).await.unwrap();
+            }
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            socket.write_all(&[0x81, 1, b'b']).await.unwrap();
+
+            let mut buf = [0; 1024];
+            while let Ok(1..) = socket.read(&mut buf).await {}
+        });
+
+        let opts = ConnectOptions::new().data_timeout(Duration::from_millis(100));
+        let mut ws = connect_with(&url, opts).await.unwrap();
+
+        // The pump waits on the full reader well past the data timeout
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        for _ in 0..101 {
+            assert_eq!(ws.rx.recv().await.unwrap().unwrap(), b"a");
+        }
+        assert_eq!(ws.rx.recv().await.unwrap().unwrap(), b"b");
+    }
+
+    #[tokio::test]
+    async fn sends_client_pings() {
+        let (url, mut client) = serve(vec![]).await;
+        let opts = ConnectOptions::new().ping_interval(Duration::from_millis(20));
+        let _ws = connect_with(&url, opts).await.unwrap();
+
+        // Masked ping, FIN set and an empty payload
+        let frame = client.recv().await.unwrap();
+        assert_eq!(frame[..2], [0x89, 0x80]);
+    }
+
+    #[tokio::test]
+    async fn backoff_grows_across_dropped_connections() {
+        // Completes every handshake and hangs up right away
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                upgrade(&mut socket).await;
+            }
+        });
+
+        let backoff = Backoff {
+            initial: Duration::from_millis(10),
+            max: Duration::from_secs(10),
+            jitter: 0.0,
+            max_attempts: Some(3),
+            ..Backoff::default()
+        };
+        let mut ws = connect_with(&url, ConnectOptions::new().reconnect(backoff)).await.unwrap();
+
+        // The first session and the 3 reconnected ones each end in an error, then the handle closes
+        let errors = tokio::time::timeout(Duration::from_secs(5), async {
+            let mut errors = 0;
+            while let Some(res) = ws.rx.recv().await {
+                assert!(res.is_err());
+                errors += 1;
+            }
+            errors
+        });
+        assert_eq!(errors.await.unwrap(), 4);
+    }
+
+    #[test]
+    fn close_code_and_reason() {
+        let closed = |frame| match process_frame(Ok(frame)) {
+            FrameResult::Error(WsError::Closed { code, reason }) => (code, reason),
+            _ => panic!("expected a close"),
+        };
+
+        assert_eq!(closed(Frame::close(1008, b"too many requests")), (1008, "too many requests".to_string()));
+        assert_eq!(closed(Frame::close_raw(vec![].into())), (1005, String::new()));
+    }
+
+    #[test]
+    fn backoff_delay() {
+        let backoff = Backoff {
+            jitter: 0.0,
+            ..Backoff::default()
+        };
+        assert_eq!(backoff.delay(0), Duration::from_millis(100));
+        assert_eq!(backoff.delay(3), Duration::from_millis(800));
+        assert_eq!(backoff.delay(20), Duration::from_secs(30));
+
+        // Jitter only shortens the delay, by half at most
+        let jittered = Backoff::default();
+        for attempt in 0..10 {
+            let delay = jittered.delay(attempt);
+            assert!(delay <= backoff.delay(attempt) && delay >= backoff.delay(attempt) / 2);
+        }
+    }
+}